@@ -22,21 +22,21 @@ use graph::Graph;
 use algorithm::visitor::Visitor;
 use algorithm::visited::Visited;
 
-pub struct BFSBrowser<'a, G, V>
+pub struct BFSBrowser<'a, 'b, G, V>
     where G: 'a + Graph<'a>,
-          V: 'a + Visitor
+          V: 'b + Visitor
 {
     g: &'a G,
-    visitor: &'a mut V,
+    visitor: &'b mut V,
     visited: Visited,
     queue: VecDeque<(usize, Option<usize>)>,
 }
 
-impl<'a, G, V> BFSBrowser<'a, G, V>
+impl<'a, 'b, G, V> BFSBrowser<'a, 'b, G, V>
     where G: 'a + Graph<'a>,
-          V: 'a + Visitor
+          V: 'b + Visitor
 {
-    pub fn new(g: &'a G, visitor: &'a mut V) -> BFSBrowser<'a, G, V> {
+    pub fn new(g: &'a G, visitor: &'b mut V) -> BFSBrowser<'a, 'b, G, V> {
         BFSBrowser {
             g,
             visitor,
@@ -0,0 +1,294 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::cmp;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use graph::basic_graph::BasicGraph;
+use graph::GraphBuilder;
+use graph::UndirectedGraph;
+
+/// Hopcroft-Tarjan: a DFS computes, for every vertex u, the discovery time `disc[u]` and the
+/// lowest discovery time `low[u]` reachable from the subtree of u with at most one back edge.
+/// Edges are identified by their main edge (see `UndirectedGraph::main_edge`).
+pub struct BiconnectedComponentsBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    time: usize,
+    disc: Vec<Option<usize>>,
+    low: Vec<usize>,
+    edge_stack: Vec<usize>,
+    is_articulation_point: Vec<bool>,
+    bridges: Vec<usize>,
+    components: Vec<HashSet<usize>>,
+    components_vertices: Vec<Vec<usize>>,
+}
+
+impl<'a, G> BiconnectedComponentsBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> BiconnectedComponentsBrowser<'a, G> {
+        BiconnectedComponentsBrowser {
+            g,
+            time: 0,
+            disc: vec![None; g.vertices_max()],
+            low: vec![0; g.vertices_max()],
+            edge_stack: Vec::new(),
+            is_articulation_point: vec![false; g.vertices_max()],
+            bridges: Vec::new(),
+            components: Vec::new(),
+            components_vertices: Vec::new(),
+        }
+    }
+
+    pub fn browse(&mut self) -> BiconnectedComponents {
+        for u in self.g.vertices_iter() {
+            if self.disc[u].is_none() {
+                self.browse_from(u);
+            }
+        }
+        BiconnectedComponents {
+            articulation_points: self.is_articulation_point.iter().enumerate()
+                .filter(|&(_, &is_ap)| is_ap).map(|(u, _)| u).collect(),
+            bridges: self.bridges.clone(),
+            components: self.components.clone(),
+            components_vertices: self.components_vertices.clone(),
+        }
+    }
+
+    /// An iterative DFS: the stack holds (vertex, edge from the parent, remaining neighbors)
+    fn browse_from(&mut self, root: usize) {
+        let mut root_children = 0;
+        self.discover(root);
+        let mut stack = vec![(root, None, self.g.adjacent_edges_by_vertex_iter(root))];
+        loop {
+            let top = match stack.last_mut() {
+                None => break,
+                Some(&mut (u, parent_edge, ref mut neighbors)) =>
+                    (u, parent_edge, neighbors.next().map(|(&w, &e)| (w, e))),
+            };
+            match top {
+                (u, parent_edge, Some((w, e))) => {
+                    let edge = self.g.main_edge(e);
+                    if w == u || Some(edge) == parent_edge {
+                        continue;
+                    }
+                    let disc_u = self.disc[u].expect("u was discovered");
+                    match self.disc[w] {
+                        None => {
+                            if u == root {
+                                root_children += 1;
+                            }
+                            self.edge_stack.push(edge);
+                            self.discover(w);
+                            stack.push((w, Some(edge), self.g.adjacent_edges_by_vertex_iter(w)));
+                        }
+                        Some(disc_w) if disc_w < disc_u => { // back edge to an ancestor
+                            self.edge_stack.push(edge);
+                            self.low[u] = cmp::min(self.low[u], disc_w);
+                        }
+                        _ => {} // already seen from the descendant w
+                    }
+                }
+                (w, parent_edge, None) => {
+                    stack.pop();
+                    if let Some(&(u, _, _)) = stack.last() {
+                        self.finish_child(u, w, root, parent_edge.expect("w has a parent"));
+                    }
+                }
+            }
+        }
+        if root_children > 1 {
+            self.is_articulation_point[root] = true;
+        }
+    }
+
+    fn discover(&mut self, u: usize) {
+        self.disc[u] = Some(self.time);
+        self.low[u] = self.time;
+        self.time += 1;
+    }
+
+    /// The subtree of w, child of u, has been browsed
+    fn finish_child(&mut self, u: usize, w: usize, root: usize, edge: usize) {
+        let disc_u = self.disc[u].expect("u was discovered");
+        self.low[u] = cmp::min(self.low[u], self.low[w]);
+        if self.low[w] > disc_u {
+            self.bridges.push(edge);
+        }
+        if self.low[w] >= disc_u {
+            if u != root {
+                self.is_articulation_point[u] = true;
+            }
+            self.pop_component(edge);
+        }
+    }
+
+    /// Pop the edges of a biconnected component, down to the edge `last`
+    fn pop_component(&mut self, last: usize) {
+        let mut component = HashSet::new();
+        let mut vertices = HashSet::new();
+        while let Some(e) = self.edge_stack.pop() {
+            component.insert(e);
+            if let Some((u, v)) = self.g.get_vertices_from_edge(e) {
+                vertices.insert(u);
+                vertices.insert(v);
+            }
+            if e == last {
+                break;
+            }
+        }
+        let mut vertices: Vec<usize> = vertices.into_iter().collect();
+        vertices.sort();
+        self.components.push(component);
+        self.components_vertices.push(vertices);
+    }
+}
+
+/// The result of a `BiconnectedComponentsBrowser`
+pub struct BiconnectedComponents {
+    articulation_points: Vec<usize>,
+    bridges: Vec<usize>,
+    components: Vec<HashSet<usize>>,
+    components_vertices: Vec<Vec<usize>>,
+}
+
+impl BiconnectedComponents {
+    /// return the cut vertices, in increasing order
+    pub fn articulation_points(&self) -> &[usize] {
+        &self.articulation_points
+    }
+
+    /// return the main edges whose removal disconnects the graph
+    pub fn bridges(&self) -> &[usize] {
+        &self.bridges
+    }
+
+    /// return the biconnected components (blocks), as sets of main edges
+    pub fn components(&self) -> &[HashSet<usize>] {
+        &self.components
+    }
+
+    /// return the vertices of the i-th block, in increasing order
+    pub fn component_vertices(&self, i: usize) -> &[usize] {
+        &self.components_vertices[i]
+    }
+
+    /// Build the block-cut tree: one vertex per block, one vertex per articulation point, and
+    /// an edge between a block and each articulation point it contains.
+    pub fn block_cut_tree<'b, T>(&self) -> BlockCutTree<T>
+        where T: GraphBuilder<'b>
+    {
+        let mut tree: T = GraphBuilder::new(BasicGraph::new());
+        let block_vertices: Vec<usize> = self.components.iter().map(|_| tree.create_vertex()).collect();
+        let mut cut_vertices = HashMap::new();
+        for &u in &self.articulation_points {
+            cut_vertices.insert(u, tree.create_vertex());
+        }
+        for (i, vertices) in self.components_vertices.iter().enumerate() {
+            for u in vertices {
+                if let Some(&c) = cut_vertices.get(u) {
+                    tree.add_edge(block_vertices[i], c);
+                }
+            }
+        }
+        BlockCutTree {
+            tree,
+            block_vertices,
+            cut_vertices,
+        }
+    }
+}
+
+/// A block-cut tree (a forest if the graph is not connected)
+pub struct BlockCutTree<T> {
+    tree: T,
+    block_vertices: Vec<usize>,
+    cut_vertices: HashMap<usize, usize>,
+}
+
+impl<T> BlockCutTree<T> {
+    pub fn tree(&self) -> &T {
+        &self.tree
+    }
+
+    /// return the vertex of the tree that represents the i-th block
+    pub fn block_vertex(&self, i: usize) -> usize {
+        self.block_vertices[i]
+    }
+
+    /// return the vertex of the tree that represents the articulation point u, if any
+    pub fn cut_vertex(&self, u: usize) -> Option<usize> {
+        self.cut_vertices.get(&u).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::examples::graph1;
+    use graph::Graph;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    fn main_edge(g: &UndirectedSimpleGraphImpl, u: usize, v: usize) -> usize {
+        g.main_edge(g.get_edges_from_vertices_iter(u, v).next().expect("edge"))
+    }
+
+    #[test]
+    fn test_articulation_points_and_bridges() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let mut b = BiconnectedComponentsBrowser::new(&g);
+        let x = b.browse();
+        assert_eq!(&[0, 9], x.articulation_points());
+
+        let bridges: HashSet<usize> = x.bridges().iter().cloned().collect();
+        let expected: HashSet<usize> = vec![(0, 1), (2, 0), (7, 8), (9, 10)].into_iter()
+            .map(|(u, v)| main_edge(&g, u, v)).collect();
+        assert_eq!(expected, bridges);
+    }
+
+    #[test]
+    fn test_components() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let mut b = BiconnectedComponentsBrowser::new(&g);
+        let x = b.browse();
+        assert_eq!(6, x.components().len());
+        let expected: HashSet<usize> = vec![(9, 11), (9, 12), (11, 12)].into_iter()
+            .map(|(u, v)| main_edge(&g, u, v)).collect();
+        assert!(x.components().contains(&expected));
+        let i = x.components().iter().position(|c| c.len() == 6).expect("a block of 6 edges");
+        assert_eq!(&[0, 3, 4, 5, 6], x.component_vertices(i));
+    }
+
+    #[test]
+    fn test_block_cut_tree() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let mut b = BiconnectedComponentsBrowser::new(&g);
+        let x = b.browse();
+        let bct: BlockCutTree<UndirectedSimpleGraphImpl> = x.block_cut_tree();
+        assert_eq!(8, bct.tree().vertices_size());
+        assert_eq!(5, bct.tree().edges_iter().count());
+        let c = bct.cut_vertex(0).expect("0 is a cut vertex");
+        assert_eq!(3, bct.tree().adjacent_vertices_iter(c).count());
+        assert_eq!(None, bct.cut_vertex(1));
+    }
+}
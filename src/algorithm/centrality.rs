@@ -0,0 +1,357 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use graph::DecoratedGraph;
+use graph::DirectedGraph;
+use graph::Graph;
+use graph::UndirectedGraph;
+
+/// The result of a single source shortest paths computation, as needed by Brandes' algorithm
+struct ShortestPathsDag {
+    /// the vertices in order of non-decreasing distance from the source
+    order: Vec<usize>,
+    /// the number of shortest paths from the source
+    sigma: Vec<f64>,
+    /// the (vertex, edge) before the vertex on a shortest path
+    predecessors: Vec<Vec<(usize, usize)>>,
+    dist: Vec<Option<usize>>,
+}
+
+/// Betweenness (Brandes), closeness and harmonic centralities. The graph may be weighted: the
+/// weights are the values of the edges (see `DijkstraBrowser`), and the shortest paths are
+/// computed with Dijkstra instead of BFS.
+pub struct CentralityBrowser<'a, G>
+    where G: 'a + Graph<'a>
+{
+    g: &'a G,
+    undirected: bool,
+    weighted: bool,
+    /// (neighbor, edge, weight)
+    adjacent: Vec<Vec<(usize, usize, usize)>>,
+}
+
+impl<'a, G> CentralityBrowser<'a, G>
+    where G: 'a + Graph<'a>
+{
+    pub fn directed(g: &'a G) -> CentralityBrowser<'a, G>
+        where G: DirectedGraph<'a>
+    {
+        Self::new(g, false, None)
+    }
+
+    pub fn undirected(g: &'a G) -> CentralityBrowser<'a, G>
+        where G: UndirectedGraph<'a>
+    {
+        Self::new(g, true, None)
+    }
+
+    pub fn weighted_directed<V>(g: &'a G) -> CentralityBrowser<'a, G>
+        where G: DirectedGraph<'a> + DecoratedGraph<'a, V, &'a usize>,
+              V: 'a + PartialEq + Clone + Debug
+    {
+        Self::new(g, false, Some(Self::weight::<V>))
+    }
+
+    pub fn weighted_undirected<V>(g: &'a G) -> CentralityBrowser<'a, G>
+        where G: UndirectedGraph<'a> + DecoratedGraph<'a, V, &'a usize>,
+              V: 'a + PartialEq + Clone + Debug
+    {
+        Self::new(g, true, Some(Self::weight::<V>))
+    }
+
+    fn weight<V>(g: &'a G, u: usize, v: usize) -> usize
+        where G: DecoratedGraph<'a, V, &'a usize>,
+              V: 'a + PartialEq + Clone + Debug
+    {
+        g.edges_values_iter(u, v).filter_map(|(_, o_weight)| o_weight).cloned().min().unwrap_or(0)
+    }
+
+    fn new(g: &'a G, undirected: bool, weight: Option<fn(&'a G, usize, usize) -> usize>) -> CentralityBrowser<'a, G> {
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            for (&v, &e) in g.adjacent_edges_by_vertex_iter(u) {
+                let w = match weight {
+                    None => 1,
+                    Some(f) => f(g, u, v),
+                };
+                adjacent[u].push((v, e, w));
+            }
+        }
+        CentralityBrowser {
+            g,
+            undirected,
+            weighted: weight.is_some(),
+            adjacent,
+        }
+    }
+
+    /// Brandes' algorithm. If `normalized`, the scores are divided by the number of pairs of
+    /// vertices (not including the vertex, for the vertex betweenness).
+    pub fn betweenness(&self, normalized: bool) -> Betweenness {
+        let mut vertex = vec![0.0; self.g.vertices_max()];
+        let mut edge = vec![0.0; self.g.edges_max()];
+        for s in self.g.vertices_iter() {
+            let dag = self.shortest_paths_dag(s);
+            let mut delta = vec![0.0; self.g.vertices_max()];
+            for &w in dag.order.iter().rev() {
+                for &(v, e) in &dag.predecessors[w] {
+                    let c = dag.sigma[v] / dag.sigma[w] * (1.0 + delta[w]);
+                    edge[e] += c;
+                    delta[v] += c;
+                }
+                if w != s {
+                    vertex[w] += delta[w];
+                }
+            }
+        }
+        if self.undirected {
+            // every undirected edge is seen once in each direction: merge the twin edges
+            for e in self.g.edges_iter() {
+                if let Some(e2) = self.g.get_reversed_edge(e) {
+                    let total = edge[e] + edge[e2];
+                    edge[e] = total;
+                    edge[e2] = total;
+                }
+            }
+        }
+        let n = self.g.vertices_size() as f64;
+        let (vertex_scale, edge_scale) = if normalized {
+            (if n > 2.0 { 1.0 / ((n - 1.0) * (n - 2.0)) } else { 1.0 },
+             if n > 1.0 { 1.0 / (n * (n - 1.0)) } else { 1.0 })
+        } else if self.undirected {
+            (0.5, 0.5)
+        } else {
+            (1.0, 1.0)
+        };
+        Betweenness {
+            vertex: vertex.into_iter().map(|b| b * vertex_scale).collect(),
+            edge: edge.into_iter().map(|b| b * edge_scale).collect(),
+        }
+    }
+
+    /// The closeness of u is (r - 1) / (sum of the distances from u to the r - 1 vertices
+    /// reachable from u), multiplied by (r - 1) / (n - 1) to compare the scores of the vertices
+    /// of different components (Wasserman and Faust).
+    pub fn closeness(&self) -> Vec<f64> {
+        let n = self.g.vertices_size() as f64;
+        let mut closeness = vec![0.0; self.g.vertices_max()];
+        for u in self.g.vertices_iter() {
+            let dag = self.shortest_paths_dag(u);
+            let reachable = (dag.order.len() - 1) as f64;
+            let total: usize = dag.dist.iter().filter_map(|&d| d).sum();
+            if total > 0 {
+                closeness[u] = reachable / total as f64 * reachable / (n - 1.0);
+            }
+        }
+        closeness
+    }
+
+    /// The harmonic centrality of u is the sum of the inverses of the distances from u to the
+    /// other vertices (unreachable vertices count for 0).
+    pub fn harmonic(&self) -> Vec<f64> {
+        let mut harmonic = vec![0.0; self.g.vertices_max()];
+        for u in self.g.vertices_iter() {
+            let dag = self.shortest_paths_dag(u);
+            harmonic[u] = dag.dist.iter().filter_map(|&d| d).filter(|&d| d > 0).map(|d| 1.0 / d as f64).sum();
+        }
+        harmonic
+    }
+
+    fn shortest_paths_dag(&self, s: usize) -> ShortestPathsDag {
+        let mut dag = ShortestPathsDag {
+            order: Vec::new(),
+            sigma: vec![0.0; self.g.vertices_max()],
+            predecessors: vec![Vec::new(); self.g.vertices_max()],
+            dist: vec![None; self.g.vertices_max()],
+        };
+        dag.sigma[s] = 1.0;
+        dag.dist[s] = Some(0);
+        if self.weighted {
+            self.dijkstra(s, &mut dag);
+        } else {
+            self.bfs(s, &mut dag);
+        }
+        dag
+    }
+
+    fn bfs(&self, s: usize, dag: &mut ShortestPathsDag) {
+        let mut queue = VecDeque::new();
+        queue.push_back(s);
+        while let Some(u) = queue.pop_front() {
+            dag.order.push(u);
+            let dist_u = dag.dist[u].expect("u was reached");
+            for &(v, e, _) in &self.adjacent[u] {
+                if dag.dist[v].is_none() {
+                    dag.dist[v] = Some(dist_u + 1);
+                    queue.push_back(v);
+                }
+                if dag.dist[v] == Some(dist_u + 1) {
+                    dag.sigma[v] += dag.sigma[u];
+                    dag.predecessors[v].push((u, e));
+                }
+            }
+        }
+    }
+
+    fn dijkstra(&self, s: usize, dag: &mut ShortestPathsDag) {
+        let mut black = vec![false; self.g.vertices_max()];
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0, s)));
+        while let Some(Reverse((dist_u, u))) = heap.pop() {
+            if black[u] {
+                continue;
+            }
+            black[u] = true;
+            dag.order.push(u);
+            for &(v, e, w) in &self.adjacent[u] {
+                let dist_v = dist_u + w;
+                match dag.dist[v] {
+                    Some(d) if d < dist_v => {}
+                    Some(d) if d == dist_v => {
+                        if !black[v] {
+                            dag.sigma[v] += dag.sigma[u];
+                            dag.predecessors[v].push((u, e));
+                        }
+                    }
+                    _ => {
+                        dag.dist[v] = Some(dist_v);
+                        dag.sigma[v] = dag.sigma[u];
+                        dag.predecessors[v] = vec![(u, e)];
+                        heap.push(Reverse((dist_v, v)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Betweenness scores, indexed by vertex and by edge. For an undirected graph, both twin edges
+/// have the same score.
+pub struct Betweenness {
+    vertex: Vec<f64>,
+    edge: Vec<f64>,
+}
+
+impl Betweenness {
+    pub fn vertex(&self, u: usize) -> f64 {
+        self.vertex[u]
+    }
+
+    pub fn edge(&self, e: usize) -> f64 {
+        self.edge[e]
+    }
+
+    pub fn vertices_scores(&self) -> &[f64] {
+        &self.vertex
+    }
+
+    pub fn edges_scores(&self) -> &[f64] {
+        &self.edge
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::GraphBuilder;
+    use graph::GraphDecorator;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    fn path<'a, T>(n: usize) -> T
+        where T: Graph<'a> + GraphBuilder<'a>
+    {
+        let mut g: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        for u in 1..n {
+            g.add_edge(u - 1, u);
+        }
+        g
+    }
+
+    fn edge(g: &UndirectedSimpleGraphImpl, u: usize, v: usize) -> usize {
+        g.get_edges_from_vertices_iter(u, v).next().expect("an edge")
+    }
+
+    #[test]
+    fn test_betweenness_undirected() {
+        let g: UndirectedSimpleGraphImpl = path(4);
+        let b = CentralityBrowser::undirected(&g);
+        let x = b.betweenness(false);
+        assert_eq!(&[0.0, 2.0, 2.0, 0.0], x.vertices_scores());
+        assert_eq!(3.0, x.edge(edge(&g, 0, 1)));
+        assert_eq!(3.0, x.edge(edge(&g, 1, 0)));
+        assert_eq!(4.0, x.edge(edge(&g, 1, 2)));
+        let x = b.betweenness(true);
+        assert!((x.vertex(1) - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_betweenness_directed() {
+        let g: DirectedSimpleGraphImpl = path(3);
+        let x = CentralityBrowser::directed(&g).betweenness(false);
+        assert_eq!(&[0.0, 1.0, 0.0], x.vertices_scores());
+    }
+
+    #[test]
+    fn test_betweenness_weighted() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut dg = GraphDecorator::new(&mut g);
+        for c in "ABCD".chars() {
+            dg.add_vertex(c);
+        }
+        // A-B-C is shorter than A-C, and A-D-C is as short as A-B-C
+        dg.add_edge(0, 1, 1);
+        dg.add_edge(1, 2, 1);
+        dg.add_edge(0, 2, 5);
+        dg.add_edge(0, 3, 1);
+        dg.add_edge(3, 2, 1);
+        let x = CentralityBrowser::weighted_undirected(&dg).betweenness(false);
+        assert_eq!(0.5, x.vertex(1));
+        assert_eq!(0.5, x.vertex(3));
+        let x = CentralityBrowser::undirected(&dg).betweenness(false);
+        assert_eq!(0.0, x.vertex(1));
+    }
+
+    #[test]
+    fn test_closeness_and_harmonic() {
+        let g: UndirectedSimpleGraphImpl = path(4);
+        let b = CentralityBrowser::undirected(&g);
+        let closeness = b.closeness();
+        assert_eq!(0.5, closeness[0]);
+        assert_eq!(0.75, closeness[1]);
+        let harmonic = b.harmonic();
+        assert!((harmonic[0] - (1.0 + 1.0 / 2.0 + 1.0 / 3.0)).abs() < 1e-9);
+        assert_eq!(2.5, harmonic[1]);
+
+        let g: DirectedSimpleGraphImpl = path(4);
+        let closeness = CentralityBrowser::directed(&g).closeness();
+        assert_eq!(0.0, closeness[3]);
+        assert_eq!(1.0 / 3.0, closeness[2]);
+    }
+}
@@ -0,0 +1,180 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::collections::HashSet;
+
+use algorithm::cores::CoresBrowser;
+use graph::UndirectedGraph;
+
+/// Maximal cliques of an undirected graph (Bron-Kerbosch with pivoting, the outer level
+/// following a degeneracy ordering). Self loops are ignored.
+pub struct CliquesBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    adjacent: Vec<HashSet<usize>>,
+}
+
+impl<'a, G> CliquesBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> CliquesBrowser<'a, G> {
+        let mut adjacent = vec![HashSet::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            adjacent[u].extend(g.adjacent_vertices_iter(u).filter(|&v| v != u));
+        }
+        CliquesBrowser {
+            g,
+            adjacent,
+        }
+    }
+
+    /// return a lazy iterator on the maximal cliques. Every clique is sorted.
+    pub fn maximal_cliques_iter(&self) -> MaximalCliquesIter<'_> {
+        MaximalCliquesIter {
+            adjacent: &self.adjacent,
+            order: CoresBrowser::new(self.g).degeneracy_order().to_vec(),
+            visited: vec![false; self.adjacent.len()],
+            i: 0,
+            stack: Vec::new(),
+        }
+    }
+
+    /// return a clique of maximum size
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        self.maximal_cliques_iter().max_by_key(|c| c.len()).unwrap_or_default()
+    }
+}
+
+/// A step of the Bron-Kerbosch recursion: the clique r, the candidates p, the excluded
+/// vertices x, and the candidates that remain to be tried (p minus the neighbors of the pivot)
+struct Frame {
+    r: Vec<usize>,
+    p: HashSet<usize>,
+    x: HashSet<usize>,
+    to_try: Vec<usize>,
+}
+
+/// An iterator on maximal cliques. The recursion is replaced by a stack, hence the
+/// caller may stop at any time.
+pub struct MaximalCliquesIter<'b> {
+    adjacent: &'b [HashSet<usize>],
+    order: Vec<usize>,
+    visited: Vec<bool>,
+    i: usize,
+    stack: Vec<Frame>,
+}
+
+impl<'b> MaximalCliquesIter<'b> {
+    fn new_frame(&self, r: Vec<usize>, p: HashSet<usize>, x: HashSet<usize>) -> Frame {
+        // the pivot maximizes |p & N(pivot)|
+        let adjacent = self.adjacent;
+        let pivot = p.iter().chain(x.iter())
+            .max_by_key(|&&u| p.iter().filter(|v| adjacent[u].contains(v)).count());
+        let to_try = match pivot {
+            None => Vec::new(),
+            Some(&u) => p.iter().filter(|v| !adjacent[u].contains(v)).cloned().collect(),
+        };
+        Frame { r, p, x, to_try }
+    }
+}
+
+impl<'b> Iterator for MaximalCliquesIter<'b> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        loop {
+            let (r, p, x): (Vec<usize>, HashSet<usize>, HashSet<usize>) = match self.stack.last_mut() {
+                None => {
+                    // outer level: v and its neighbors that come later in the order
+                    let &v = self.order.get(self.i)?;
+                    self.i += 1;
+                    self.visited[v] = true;
+                    let (x, p) = self.adjacent[v].iter().partition(|&&u| self.visited[u]);
+                    (vec![v], p, x)
+                }
+                Some(frame) => {
+                    match frame.to_try.pop() {
+                        None => {
+                            self.stack.pop();
+                            continue;
+                        }
+                        Some(v) => {
+                            let adjacent_v = &self.adjacent[v];
+                            let mut r = frame.r.clone();
+                            r.push(v);
+                            let p = frame.p.iter().filter(|u| adjacent_v.contains(u)).cloned().collect();
+                            let x = frame.x.iter().filter(|u| adjacent_v.contains(u)).cloned().collect();
+                            frame.p.remove(&v);
+                            frame.x.insert(v);
+                            (r, p, x)
+                        }
+                    }
+                }
+            };
+            if p.is_empty() {
+                if x.is_empty() {
+                    let mut clique = r;
+                    clique.sort();
+                    return Some(clique);
+                }
+            } else {
+                let frame = self.new_frame(r, p, x);
+                self.stack.push(frame);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::examples::graph1;
+    use graph::GraphBuilder;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    #[test]
+    fn test_maximal_cliques() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let b = CliquesBrowser::new(&g);
+        let mut cliques: Vec<Vec<usize>> = b.maximal_cliques_iter().collect();
+        cliques.sort();
+        assert_eq!(vec![vec![0, 1], vec![0, 2], vec![0, 5], vec![0, 6], vec![3, 4, 5], vec![4, 6],
+                        vec![7, 8], vec![9, 10], vec![9, 11, 12]], cliques);
+        assert_eq!(2, b.maximal_cliques_iter().take(2).count());
+    }
+
+    #[test]
+    fn test_maximum_clique() {
+        // K4 (0, 1, 2, 3) plus a triangle (3, 4, 5) and an isolated vertex
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..7 {
+            g.create_vertex();
+        }
+        for &(u, v) in &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3), (3, 4), (4, 5), (5, 3)] {
+            g.add_edge(u, v);
+        }
+        let b = CliquesBrowser::new(&g);
+        assert_eq!(vec![0, 1, 2, 3], b.maximum_clique());
+        assert_eq!(3, b.maximal_cliques_iter().count());
+        assert!(b.maximal_cliques_iter().any(|c| c == vec![6]));
+    }
+}
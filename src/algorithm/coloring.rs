@@ -0,0 +1,270 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::cmp;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use algorithm::cores::CoresBrowser;
+use graph::Graph;
+use graph::GraphBuilder;
+use graph::GraphDecorator;
+use graph::UndirectedGraph;
+
+/// The order in which the greedy coloring processes the vertices
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GreedyOrdering {
+    /// by increasing vertex index
+    Natural,
+    /// by decreasing degree (Welsh-Powell)
+    LargestFirst,
+    /// the reverse of the order obtained by removing repeatedly a vertex of minimum degree
+    SmallestLast,
+}
+
+/// A proper vertex coloring: adjacent vertices have different colors. Colors are 0, 1, 2, ...
+#[derive(Clone, Debug)]
+pub struct Coloring {
+    colors: Vec<Option<usize>>,
+    colors_count: usize,
+}
+
+impl Coloring {
+    /// return the color of a vertex
+    pub fn color(&self, u: usize) -> Option<usize> {
+        self.colors.get(u).cloned().unwrap_or(None)
+    }
+
+    /// return the colors, indexed by vertex
+    pub fn colors(&self) -> &[Option<usize>] {
+        &self.colors
+    }
+
+    /// return the number of colors used
+    pub fn colors_count(&self) -> usize {
+        self.colors_count
+    }
+
+    /// Write the colors in a decorator, as vertex values. `f` maps a color to a value,
+    /// e.g. the name of a Graphviz color.
+    pub fn decorate<'b, G, V, E, F>(&self, dg: &mut GraphDecorator<'b, G, V, E>, f: F)
+        where G: Graph<'b> + GraphBuilder<'b>,
+              V: 'b + PartialEq + Clone + Debug,
+              E: 'b + PartialEq + Clone + Debug,
+              F: Fn(usize) -> V
+    {
+        for (u, &o_color) in self.colors.iter().enumerate() {
+            if let Some(color) = o_color {
+                dg.set_vertex_value(u, f(color));
+            }
+        }
+    }
+}
+
+/// Vertex coloring of an undirected graph. Self loops are ignored.
+pub struct ColoringBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    adjacent: Vec<Vec<usize>>,
+}
+
+impl<'a, G> ColoringBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> ColoringBrowser<'a, G> {
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            adjacent[u].extend(g.adjacent_vertices_iter(u).filter(|&v| v != u));
+        }
+        ColoringBrowser {
+            g,
+            adjacent,
+        }
+    }
+
+    /// Color the vertices one after the other, with the smallest color that is not used by
+    /// a neighbor
+    pub fn greedy(&self, ordering: GreedyOrdering) -> Coloring {
+        let order = match ordering {
+            GreedyOrdering::Natural => self.g.vertices_iter().collect(),
+            GreedyOrdering::LargestFirst => self.largest_first_order(),
+            GreedyOrdering::SmallestLast => self.smallest_last_order(),
+        };
+        let mut colors = vec![None; self.adjacent.len()];
+        let mut colors_count = 0;
+        for u in order {
+            let color = self.smallest_free_color(&colors, u);
+            colors[u] = Some(color);
+            colors_count = cmp::max(colors_count, color + 1);
+        }
+        Coloring { colors, colors_count }
+    }
+
+    /// DSatur (Brélaz): color first the vertex whose neighbors use the largest number of distinct
+    /// colors, ties broken by degree
+    pub fn dsatur(&self) -> Coloring {
+        let mut colors = vec![None; self.adjacent.len()];
+        let mut neighbor_colors: Vec<HashSet<usize>> = vec![HashSet::new(); self.adjacent.len()];
+        let mut colors_count = 0;
+        let mut uncolored: Vec<usize> = self.g.vertices_iter().collect();
+        while !uncolored.is_empty() {
+            let i = (0..uncolored.len()).max_by_key(|&i| {
+                let u = uncolored[i];
+                (neighbor_colors[u].len(), self.adjacent[u].len(), Reverse(u))
+            }).expect("not empty");
+            let u = uncolored.swap_remove(i);
+            let color = self.smallest_free_color(&colors, u);
+            colors[u] = Some(color);
+            colors_count = cmp::max(colors_count, color + 1);
+            for &v in &self.adjacent[u] {
+                neighbor_colors[v].insert(color);
+            }
+        }
+        Coloring { colors, colors_count }
+    }
+
+    /// Find a coloring with the minimum number of colors (the chromatic number) by
+    /// branch-and-bound. The complexity is exponential: use it on small graphs only.
+    pub fn exact(&self) -> Coloring {
+        let mut best = self.dsatur();
+        let order = self.largest_first_order();
+        let mut colors = vec![None; self.adjacent.len()];
+        self.exact_helper(&order, 0, &mut colors, 0, &mut best);
+        best
+    }
+
+    fn exact_helper(&self, order: &[usize], i: usize, colors: &mut Vec<Option<usize>>,
+                    colors_count: usize, best: &mut Coloring) {
+        if i == order.len() {
+            *best = Coloring { colors: colors.clone(), colors_count };
+            return;
+        }
+        let u = order[i];
+        for color in 0..colors_count + 1 {
+            let new_colors_count = cmp::max(colors_count, color + 1);
+            if new_colors_count >= best.colors_count {
+                break;
+            }
+            if self.adjacent[u].iter().all(|&v| colors[v] != Some(color)) {
+                colors[u] = Some(color);
+                self.exact_helper(order, i + 1, colors, new_colors_count, best);
+                colors[u] = None;
+            }
+        }
+    }
+
+    fn smallest_free_color(&self, colors: &[Option<usize>], u: usize) -> usize {
+        let used: HashSet<usize> = self.adjacent[u].iter().filter_map(|&v| colors[v]).collect();
+        (0..).find(|c| !used.contains(c)).expect("there is a free color")
+    }
+
+    fn largest_first_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = self.g.vertices_iter().collect();
+        order.sort_by_key(|&u| Reverse(self.adjacent[u].len()));
+        order
+    }
+
+    fn smallest_last_order(&self) -> Vec<usize> {
+        let mut order = CoresBrowser::new(self.g).degeneracy_order().to_vec();
+        order.reverse();
+        order
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::examples::graph1;
+    use graph::examples::graph2;
+    use graph::UndirectedSimpleGraphImpl;
+    use util::GraphvizBuilder;
+    use util::GraphvizBuilderUndirectedImpl;
+    use util::GraphvizWriter;
+
+    use super::*;
+
+    fn assert_is_proper(g: &UndirectedSimpleGraphImpl, coloring: &Coloring) {
+        for u in g.vertices_iter() {
+            let color = coloring.color(u).expect("a color");
+            assert!(color < coloring.colors_count());
+            for v in g.adjacent_vertices_iter(u) {
+                assert_ne!(Some(color), coloring.color(v));
+            }
+        }
+    }
+
+    #[test]
+    fn test_greedy() {
+        let g = graph2::<UndirectedSimpleGraphImpl>();
+        let b = ColoringBrowser::new(&g);
+        for &ordering in &[GreedyOrdering::Natural, GreedyOrdering::LargestFirst, GreedyOrdering::SmallestLast] {
+            let coloring = b.greedy(ordering);
+            assert_is_proper(&g, &coloring);
+        }
+    }
+
+    #[test]
+    fn test_dsatur_and_exact() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let b = ColoringBrowser::new(&g);
+        let coloring = b.dsatur();
+        assert_is_proper(&g, &coloring);
+        let coloring = b.exact();
+        assert_is_proper(&g, &coloring);
+        assert_eq!(3, coloring.colors_count());
+    }
+
+    #[test]
+    fn test_exact_odd_cycle() {
+        // an odd cycle needs 3 colors, an even cycle needs 2 colors
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..5 {
+            g.create_vertex();
+        }
+        for u in 0..5 {
+            g.add_edge(u, (u + 1) % 5);
+        }
+        let b = ColoringBrowser::new(&g);
+        assert_eq!(3, b.exact().colors_count());
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..6 {
+            g.create_vertex();
+        }
+        for u in 0..6 {
+            g.add_edge(u, (u + 1) % 6);
+        }
+        let b = ColoringBrowser::new(&g);
+        assert_eq!(2, b.exact().colors_count());
+    }
+
+    #[test]
+    fn test_graphviz() {
+        let mut g = graph1::<UndirectedSimpleGraphImpl>();
+        let coloring = ColoringBrowser::new(&g).dsatur();
+        let mut dg: GraphDecorator<UndirectedSimpleGraphImpl, &'static str, usize> = GraphDecorator::new(&mut g);
+        coloring.decorate(&mut dg, |c| ["red", "green", "blue", "yellow"][c]);
+        let v = Vec::new();
+        let h = GraphvizBuilderUndirectedImpl::new(&dg, &v);
+        let gw = GraphvizWriter::new(&h);
+        gw.output("gv_output/coloring.dot");
+        assert!(h.build_string().contains("[label=red]"));
+    }
+}
@@ -0,0 +1,406 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use graph::DecoratedGraph;
+use graph::UndirectedGraph;
+
+/// A partition of the vertices into communities
+pub struct Communities {
+    community: Vec<Option<usize>>,
+    count: usize,
+    modularity: f64,
+}
+
+impl Communities {
+    /// return the community of a vertex. Communities are numbered from 0.
+    pub fn community(&self, u: usize) -> Option<usize> {
+        self.community.get(u).cloned().unwrap_or(None)
+    }
+
+    /// return the communities, indexed by vertex
+    pub fn communities(&self) -> &[Option<usize>] {
+        &self.community
+    }
+
+    /// return the number of communities
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn modularity(&self) -> f64 {
+        self.modularity
+    }
+}
+
+/// A small xorshift generator: the order of the vertices in label propagation is random, but
+/// reproducible.
+struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn shuffle(&mut self, v: &mut [usize]) {
+        for i in (1..v.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            v.swap(i, j);
+        }
+    }
+}
+
+/// Community detection on an undirected graph. Edges may be weighted by their values (see
+/// `DijkstraBrowser`). Self loops are ignored.
+pub struct CommunityBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    /// (neighbor, weight)
+    adjacent: Vec<Vec<(usize, f64)>>,
+    seed: u64,
+    max_iterations: usize,
+}
+
+impl<'a, G> CommunityBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> CommunityBrowser<'a, G> {
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            adjacent[u].extend(g.adjacent_vertices_iter(u).filter(|&v| v != u).map(|v| (v, 1.0)));
+        }
+        Self::with_adjacent(g, adjacent)
+    }
+
+    pub fn weighted<V>(g: &'a G) -> CommunityBrowser<'a, G>
+        where G: DecoratedGraph<'a, V, &'a usize>,
+              V: 'a + PartialEq + Clone + Debug
+    {
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            for v in g.adjacent_vertices_iter(u).filter(|&v| v != u) {
+                let w = g.edges_values_iter(u, v).filter_map(|(_, o_weight)| o_weight).cloned().min().unwrap_or(0);
+                adjacent[u].push((v, w as f64));
+            }
+        }
+        Self::with_adjacent(g, adjacent)
+    }
+
+    fn with_adjacent(g: &'a G, adjacent: Vec<Vec<(usize, f64)>>) -> CommunityBrowser<'a, G> {
+        CommunityBrowser {
+            g,
+            adjacent,
+            seed: 0x2545_f491_4f6c_dd1d,
+            max_iterations: 100,
+        }
+    }
+
+    /// The seed of the random order of label propagation
+    pub fn with_seed(mut self, seed: u64) -> CommunityBrowser<'a, G> {
+        self.seed = if seed == 0 { 1 } else { seed };
+        self
+    }
+
+    /// The maximum number of rounds of label propagation (default: 100)
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> CommunityBrowser<'a, G> {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Newman's modularity of a partition given by a community per vertex:
+    /// Q = sum on communities c of (w_in(c) / 2m - (tot(c) / 2m)^2), where w_in(c) is twice the
+    /// weight of the edges inside c and tot(c) the sum of the degrees of the vertices of c.
+    pub fn modularity(&self, community: &[Option<usize>]) -> f64 {
+        let mut inside: HashMap<usize, f64> = HashMap::new();
+        let mut total: HashMap<usize, f64> = HashMap::new();
+        let mut m2 = 0.0;
+        for u in self.g.vertices_iter() {
+            let c = match community.get(u).cloned().unwrap_or(None) {
+                Some(c) => c,
+                None => continue,
+            };
+            for &(v, w) in &self.adjacent[u] {
+                m2 += w;
+                *total.entry(c).or_insert(0.0) += w;
+                if community.get(v).cloned().unwrap_or(None) == Some(c) {
+                    *inside.entry(c).or_insert(0.0) += w;
+                }
+            }
+        }
+        if m2 == 0.0 {
+            return 0.0;
+        }
+        total.iter().map(|(c, &tot)| inside.get(c).cloned().unwrap_or(0.0) / m2 - (tot / m2) * (tot / m2)).sum()
+    }
+
+    /// Asynchronous label propagation (Raghavan, Albert, Kumara): every vertex takes, in random
+    /// order, the label of maximum weight among its neighbors, until every vertex has such a
+    /// label or after `max_iterations` rounds: the labels may not converge.
+    pub fn label_propagation(&self) -> Communities {
+        let mut rng = XorShift { state: self.seed };
+        let mut label: Vec<Option<usize>> = vec![None; self.adjacent.len()];
+        for u in self.g.vertices_iter() {
+            label[u] = Some(u);
+        }
+        let mut order: Vec<usize> = self.g.vertices_iter().collect();
+        for _ in 0..self.max_iterations {
+            rng.shuffle(&mut order);
+            for &u in &order {
+                let best = self.best_labels(&label, u);
+                if !best.is_empty() && !best.contains(&label[u].expect("labeled")) {
+                    label[u] = Some(best[(rng.next() % best.len() as u64) as usize]);
+                }
+            }
+            let stable = order.iter().all(|&u| {
+                let best = self.best_labels(&label, u);
+                best.is_empty() || best.contains(&label[u].expect("labeled"))
+            });
+            if stable {
+                break;
+            }
+        }
+        self.communities(label)
+    }
+
+    /// return the labels of maximum weight among the neighbors of u
+    fn best_labels(&self, label: &[Option<usize>], u: usize) -> Vec<usize> {
+        let mut weights: HashMap<usize, f64> = HashMap::new();
+        for &(v, w) in &self.adjacent[u] {
+            *weights.entry(label[v].expect("labeled")).or_insert(0.0) += w;
+        }
+        let max = weights.values().cloned().fold(0.0, f64::max);
+        let mut best: Vec<usize> = weights.into_iter().filter(|&(_, w)| w == max).map(|(l, _)| l).collect();
+        best.sort();
+        best
+    }
+
+    /// Louvain (Blondel et al.): move every vertex to the neighbor community that gives the best
+    /// modularity gain until no move is possible, then merge every community into a single
+    /// vertex and start again.
+    pub fn louvain(&self) -> Communities {
+        let vertices: Vec<usize> = self.g.vertices_iter().collect();
+        // the aggregated graph: node -> (node -> weight), the self loops have the internal weights
+        let index: HashMap<usize, usize> = vertices.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+        let mut nodes: Vec<HashMap<usize, f64>> = vertices.iter().map(|&u| {
+            let mut neighbors = HashMap::new();
+            for &(v, w) in &self.adjacent[u] {
+                *neighbors.entry(index[&v]).or_insert(0.0) += w;
+            }
+            neighbors
+        }).collect();
+        // vertex index -> node of the aggregated graph
+        let mut node_of: Vec<usize> = (0..vertices.len()).collect();
+        loop {
+            let (community, moved) = Self::local_moving(&nodes);
+            if !moved {
+                break;
+            }
+            let (renumbered, count) = Self::renumber(&community);
+            for n in node_of.iter_mut() {
+                *n = renumbered[*n];
+            }
+            let mut aggregated = vec![HashMap::new(); count];
+            for (i, neighbors) in nodes.iter().enumerate() {
+                for (&j, &w) in neighbors {
+                    *aggregated[renumbered[i]].entry(renumbered[j]).or_insert(0.0) += w;
+                }
+            }
+            nodes = aggregated;
+        }
+        let mut label = vec![None; self.adjacent.len()];
+        for (i, &u) in vertices.iter().enumerate() {
+            label[u] = Some(node_of[i]);
+        }
+        self.communities(label)
+    }
+
+    /// The first phase of Louvain. Return the community of every node, and true if a node moved.
+    fn local_moving(nodes: &[HashMap<usize, f64>]) -> (Vec<usize>, bool) {
+        let degree: Vec<f64> = nodes.iter().map(|neighbors| neighbors.values().sum()).collect();
+        let m2: f64 = degree.iter().sum();
+        let mut community: Vec<usize> = (0..nodes.len()).collect();
+        let mut total = degree.clone();
+        let mut moved = false;
+        if m2 == 0.0 {
+            return (community, moved);
+        }
+        loop {
+            let mut improved = false;
+            for i in 0..nodes.len() {
+                let old = community[i];
+                let mut to_community: HashMap<usize, f64> = HashMap::new();
+                to_community.insert(old, 0.0);
+                for (&j, &w) in &nodes[i] {
+                    if j != i {
+                        *to_community.entry(community[j]).or_insert(0.0) += w;
+                    }
+                }
+                total[old] -= degree[i];
+                // the gain of moving i into c is k_i_in(c) - tot(c) * k_i / 2m, up to a factor
+                let gain = |c: usize| to_community[&c] - total[c] * degree[i] / m2;
+                let mut best = old;
+                let mut best_gain = gain(old);
+                let mut candidates: Vec<usize> = to_community.keys().cloned().collect();
+                candidates.sort();
+                for c in candidates {
+                    if gain(c) > best_gain + 1e-12 {
+                        best = c;
+                        best_gain = gain(c);
+                    }
+                }
+                total[best] += degree[i];
+                if best != old {
+                    community[i] = best;
+                    improved = true;
+                    moved = true;
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        (community, moved)
+    }
+
+    /// Renumber labels from 0. Return the new labels and their count.
+    fn renumber(labels: &[usize]) -> (Vec<usize>, usize) {
+        let mut new_labels = HashMap::new();
+        let renumbered = labels.iter().map(|l| {
+            let count = new_labels.len();
+            *new_labels.entry(*l).or_insert(count)
+        }).collect();
+        (renumbered, new_labels.len())
+    }
+
+    fn communities(&self, label: Vec<Option<usize>>) -> Communities {
+        let vertices: Vec<usize> = self.g.vertices_iter().collect();
+        let labels: Vec<usize> = vertices.iter().map(|&u| label[u].expect("labeled")).collect();
+        let (renumbered, count) = Self::renumber(&labels);
+        let mut community = vec![None; self.adjacent.len()];
+        for (i, &u) in vertices.iter().enumerate() {
+            community[u] = Some(renumbered[i]);
+        }
+        let modularity = self.modularity(&community);
+        Communities {
+            community,
+            count,
+            modularity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::examples::graph1;
+    use graph::Graph;
+    use graph::GraphBuilder;
+    use graph::GraphDecorator;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    /// two triangles linked by the edge 2-3
+    fn two_triangles() -> UndirectedSimpleGraphImpl {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..6 {
+            g.create_vertex();
+        }
+        for &(u, v) in &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 3)] {
+            g.add_edge(u, v);
+        }
+        g
+    }
+
+    #[test]
+    fn test_modularity() {
+        let g = two_triangles();
+        let b = CommunityBrowser::new(&g);
+        let q = b.modularity(&[Some(0), Some(0), Some(0), Some(1), Some(1), Some(1)]);
+        assert!((q - 2.0 * (6.0 / 14.0 - 0.25)).abs() < 1e-9);
+        assert_eq!(0.0, b.modularity(&[Some(0); 6]));
+    }
+
+    #[test]
+    fn test_louvain() {
+        let g = two_triangles();
+        let x = CommunityBrowser::new(&g).louvain();
+        assert_eq!(2, x.count());
+        assert_eq!(x.community(0), x.community(2));
+        assert_eq!(x.community(3), x.community(5));
+        assert_ne!(x.community(2), x.community(3));
+        assert!((x.modularity() - 2.0 * (6.0 / 14.0 - 0.25)).abs() < 1e-9);
+
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let x = CommunityBrowser::new(&g).louvain();
+        assert!(x.modularity() > 0.4);
+        assert_ne!(x.community(0), x.community(9));
+    }
+
+    #[test]
+    fn test_louvain_weighted() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut dg = GraphDecorator::new(&mut g);
+        for c in "ABCD".chars() {
+            dg.add_vertex(c);
+        }
+        dg.add_edge(0, 1, 10);
+        dg.add_edge(1, 2, 1);
+        dg.add_edge(2, 3, 10);
+        dg.add_edge(3, 0, 1);
+        let x = CommunityBrowser::weighted(&dg).louvain();
+        assert_eq!(2, x.count());
+        assert_eq!(x.community(0), x.community(1));
+        assert_eq!(x.community(2), x.community(3));
+    }
+
+    #[test]
+    fn test_label_propagation() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let x = CommunityBrowser::new(&g).with_seed(42).label_propagation();
+        // a community never crosses two connected components
+        assert!(x.count() >= 3);
+        for u in 0..7 {
+            for v in 7..13 {
+                assert_ne!(x.community(u), x.community(v));
+            }
+        }
+        assert_eq!(x.community(7), x.community(8));
+        for u in g.vertices_iter() {
+            assert!(x.community(u).expect("a community") < x.count());
+        }
+    }
+
+    #[test]
+    fn test_label_propagation_max_iterations() {
+        let g = two_triangles();
+        let x = CommunityBrowser::new(&g).with_max_iterations(0).label_propagation();
+        assert_eq!(6, x.count());
+        let x = CommunityBrowser::new(&g).with_max_iterations(1000).label_propagation();
+        assert!(x.count() < 6);
+    }
+}
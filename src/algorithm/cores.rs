@@ -0,0 +1,203 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::basic_graph::BasicGraph;
+use graph::GraphBuilder;
+use graph::UndirectedGraph;
+
+/// k-core decomposition of an undirected graph (Batagelj and Zaversnik), in O(V + E).
+///
+/// The vertices are kept in an array sorted by current degree, with the start of every degree
+/// bucket. The vertex of minimum degree is removed repeatedly, and its neighbors of higher
+/// degree move to the previous bucket by a swap. The order of removal is a degeneracy ordering,
+/// and the degree of a vertex when it is removed is its core number. Self loops are ignored.
+pub struct CoresBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    core: Vec<usize>,
+    order: Vec<usize>,
+}
+
+impl<'a, G> CoresBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> CoresBrowser<'a, G> {
+        let mut b = CoresBrowser {
+            g,
+            core: vec![0; g.vertices_max()],
+            order: Vec::with_capacity(g.vertices_size()),
+        };
+        b.browse();
+        b
+    }
+
+    fn browse(&mut self) {
+        let g = self.g;
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            adjacent[u].extend(g.adjacent_vertices_iter(u).filter(|&v| v != u));
+        }
+        let degree = &mut self.core;
+        let mut max_degree = 0;
+        for u in g.vertices_iter() {
+            degree[u] = adjacent[u].len();
+            if degree[u] > max_degree {
+                max_degree = degree[u];
+            }
+        }
+        // bucket_start[d] is the position of the first vertex of degree d in `vertices`
+        let mut bucket_start = vec![0; max_degree + 2];
+        for u in g.vertices_iter() {
+            bucket_start[degree[u] + 1] += 1;
+        }
+        for d in 1..bucket_start.len() {
+            bucket_start[d] += bucket_start[d - 1];
+        }
+        let mut vertices = vec![0; g.vertices_size()];
+        let mut position = vec![0; g.vertices_max()];
+        {
+            let mut next = bucket_start.clone();
+            for u in g.vertices_iter() {
+                position[u] = next[degree[u]];
+                vertices[position[u]] = u;
+                next[degree[u]] += 1;
+            }
+        }
+        for i in 0..vertices.len() {
+            let u = vertices[i];
+            for &v in &adjacent[u] {
+                if degree[v] > degree[u] {
+                    // swap v with the first vertex of its bucket, then shrink the bucket
+                    let d = degree[v];
+                    let w = vertices[bucket_start[d]];
+                    if v != w {
+                        vertices.swap(position[v], bucket_start[d]);
+                        position[w] = position[v];
+                        position[v] = bucket_start[d];
+                    }
+                    bucket_start[d] += 1;
+                    degree[v] -= 1;
+                }
+            }
+        }
+        self.order = vertices;
+    }
+
+    /// return the largest k such that u belongs to the k-core
+    pub fn core_number(&self, u: usize) -> usize {
+        self.core[u]
+    }
+
+    /// return the core numbers, indexed by vertex
+    pub fn core_numbers(&self) -> &[usize] {
+        &self.core
+    }
+
+    /// return the largest k such that the k-core is not empty
+    pub fn degeneracy(&self) -> usize {
+        self.order.iter().map(|&u| self.core[u]).max().unwrap_or(0)
+    }
+
+    /// return a degeneracy ordering: every vertex has at most `degeneracy()` neighbors later
+    /// in the order
+    pub fn degeneracy_order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// return the vertices of the k-core, the maximal subgraph whose vertices have degree >= k
+    pub fn k_core_vertices(&self, k: usize) -> Vec<usize> {
+        self.g.vertices_iter().filter(|&u| self.core[u] >= k).collect()
+    }
+
+    /// Build the k-core as a new graph. The vertices keep their indices, but not the edges.
+    pub fn k_core<'b, T>(&self, k: usize) -> T
+        where T: GraphBuilder<'b>
+    {
+        let mut sub: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..self.g.vertices_max() {
+            sub.create_vertex();
+        }
+        let mut in_core = vec![false; self.g.vertices_max()];
+        for u in self.k_core_vertices(k) {
+            in_core[u] = true;
+        }
+        for u in (0..self.g.vertices_max()).rev() {
+            if !in_core[u] {
+                sub.remove_vertex(u);
+            }
+        }
+        for e in self.g.edges_iter() {
+            if let Some((u, v)) = self.g.get_vertices_from_edge(e) {
+                if u != v && in_core[u] && in_core[v] {
+                    sub.add_edge(u, v);
+                }
+            }
+        }
+        sub
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::examples::graph1;
+    use graph::Graph;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    #[test]
+    fn test_cores() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let b = CoresBrowser::new(&g);
+        assert_eq!(2, b.degeneracy());
+        assert_eq!(vec![0, 3, 4, 5, 6, 9, 11, 12], b.k_core_vertices(2));
+        assert_eq!(1, b.core_number(1));
+        assert_eq!(2, b.core_number(0));
+        assert_eq!(g.vertices_size(), b.k_core_vertices(1).len());
+        assert!(b.k_core_vertices(3).is_empty());
+    }
+
+    #[test]
+    fn test_degeneracy_order() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let b = CoresBrowser::new(&g);
+        let order = b.degeneracy_order();
+        assert_eq!(13, order.len());
+        let mut position = [0; 13];
+        for (i, &u) in order.iter().enumerate() {
+            position[u] = i;
+        }
+        for &u in order {
+            let later = g.adjacent_vertices_iter(u).filter(|&v| position[v] > position[u]).count();
+            assert!(later <= b.degeneracy());
+        }
+    }
+
+    #[test]
+    fn test_k_core() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let b = CoresBrowser::new(&g);
+        let core: UndirectedSimpleGraphImpl = b.k_core(2);
+        assert_eq!(vec![0, 3, 4, 5, 6, 9, 11, 12], core.vertices_iter().collect::<Vec<usize>>());
+        assert_eq!(9, core.edges_iter().count());
+        assert!(core.get_edges_from_vertices_iter(0, 6).next().is_some());
+        assert!(core.get_edges_from_vertices_iter(0, 1).next().is_none());
+    }
+}
@@ -0,0 +1,332 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::collections::VecDeque;
+
+use graph::DirectedGraph;
+use graph::UndirectedGraph;
+
+/// Elementary cycles of a directed graph (Johnson, "Finding all the elementary circuits of a
+/// directed graph"). A cycle is a sequence of vertices, starting with its smallest vertex.
+pub struct DirectedCyclesBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    g: &'a G,
+}
+
+impl<'a, G> DirectedCyclesBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> DirectedCyclesBrowser<'a, G> {
+        DirectedCyclesBrowser { g }
+    }
+
+    /// A lazy iterator on the elementary cycles. If `max_length` is set, only the cycles of at
+    /// most `max_length` vertices are yielded (the blocking of Johnson's algorithm is then
+    /// replaced by a plain bounded search, since it would miss some short cycles).
+    pub fn simple_cycles_iter(&self, max_length: Option<usize>) -> SimpleCyclesIter {
+        let g = self.g;
+        let n = g.vertices_max();
+        let mut successors = vec![Vec::new(); n];
+        let mut predecessors = vec![Vec::new(); n];
+        for u in g.vertices_iter() {
+            successors[u].extend(g.adjacent_vertices_iter(u));
+            successors[u].sort();
+            successors[u].dedup();
+            for &v in &successors[u] {
+                predecessors[v].push(u);
+            }
+        }
+        let mut starts: Vec<usize> = g.vertices_iter().collect();
+        starts.sort();
+        SimpleCyclesIter {
+            successors,
+            predecessors,
+            starts,
+            next_start: 0,
+            max_length,
+            start: 0,
+            in_component: vec![false; n],
+            path: Vec::new(),
+            blocked: vec![false; n],
+            closed: vec![false; n],
+            b: vec![Vec::new(); n],
+            stack: Vec::new(),
+        }
+    }
+}
+
+/// The state of Johnson's algorithm, without recursion. The cycles through `start` are searched
+/// in the strongly connected component of `start` in the subgraph of vertices >= `start`.
+pub struct SimpleCyclesIter {
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    starts: Vec<usize>,
+    next_start: usize,
+    max_length: Option<usize>,
+    start: usize,
+    in_component: Vec<bool>,
+    path: Vec<usize>,
+    blocked: Vec<bool>,
+    // closed[u] is true if a cycle was found since u was pushed
+    closed: Vec<bool>,
+    b: Vec<Vec<usize>>,
+    // (vertex, neighbors to visit)
+    stack: Vec<(usize, Vec<usize>)>,
+}
+
+impl SimpleCyclesIter {
+    fn prepare_next_start(&mut self) -> bool {
+        while self.next_start < self.starts.len() {
+            let s = self.starts[self.next_start];
+            self.next_start += 1;
+            let forward = reach_from(s, &self.successors);
+            let backward = reach_from(s, &self.predecessors);
+            for u in 0..self.in_component.len() {
+                self.in_component[u] = forward[u] && backward[u];
+                self.blocked[u] = false;
+                self.closed[u] = false;
+                self.b[u].clear();
+            }
+            if !self.successors[s].iter().any(|&v| self.in_component[v]) {
+                continue;
+            }
+            self.start = s;
+            self.path = vec![s];
+            self.blocked[s] = true;
+            let neighbors = self.neighbors(s);
+            self.stack = vec![(s, neighbors)];
+            return true;
+        }
+        false
+    }
+
+    // reversed, to pop the neighbors in increasing order
+    fn neighbors(&self, u: usize) -> Vec<usize> {
+        self.successors[u].iter().rev().cloned().filter(|&v| self.in_component[v]).collect()
+    }
+
+    fn unblock(&mut self, u: usize) {
+        let mut stack = vec![u];
+        while let Some(w) = stack.pop() {
+            if self.blocked[w] {
+                self.blocked[w] = false;
+                stack.append(&mut self.b[w]);
+            }
+        }
+    }
+}
+
+/// The vertices >= s that are reachable from s
+fn reach_from(s: usize, adjacent: &[Vec<usize>]) -> Vec<bool> {
+    let mut reached = vec![false; adjacent.len()];
+    reached[s] = true;
+    let mut stack = vec![s];
+    while let Some(u) = stack.pop() {
+        for &v in &adjacent[u] {
+            if v >= s && !reached[v] {
+                reached[v] = true;
+                stack.push(v);
+            }
+        }
+    }
+    reached
+}
+
+impl Iterator for SimpleCyclesIter {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        loop {
+            let (u, next) = match self.stack.last_mut() {
+                None => {
+                    if !self.prepare_next_start() {
+                        return None;
+                    }
+                    continue;
+                }
+                Some(&mut (u, ref mut neighbors)) => (u, neighbors.pop()),
+            };
+            if let Some(v) = next {
+                if v == self.start {
+                    for &w in &self.path {
+                        self.closed[w] = true;
+                    }
+                    return Some(self.path.clone());
+                }
+                if !self.blocked[v] && self.max_length.map_or(true, |k| self.path.len() < k) {
+                    self.path.push(v);
+                    self.blocked[v] = true;
+                    self.closed[v] = false;
+                    let neighbors = self.neighbors(v);
+                    self.stack.push((v, neighbors));
+                }
+                continue;
+            }
+            if self.max_length.is_some() {
+                self.blocked[u] = false;
+            } else if self.closed[u] {
+                self.unblock(u);
+            } else {
+                for v in self.neighbors(u) {
+                    if !self.b[v].contains(&u) {
+                        self.b[v].push(u);
+                    }
+                }
+            }
+            self.stack.pop();
+            self.path.pop();
+        }
+    }
+}
+
+/// Cycles of an undirected graph.
+pub struct UndirectedCyclesBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+}
+
+impl<'a, G> UndirectedCyclesBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> UndirectedCyclesBrowser<'a, G> {
+        UndirectedCyclesBrowser { g }
+    }
+
+    /// A fundamental cycle basis, derived from a BFS spanning forest: every edge that is not in
+    /// the forest closes one cycle with the path of the forest between its ends. There are
+    /// E - V + C cycles, where C is the number of connected components. A cycle is a sequence of
+    /// vertices; its last vertex is adjacent to its first.
+    pub fn cycle_basis(&self) -> Vec<Vec<usize>> {
+        let g = self.g;
+        let n = g.vertices_max();
+        // vertex => (parent, main edge)
+        let mut parent: Vec<Option<(usize, usize)>> = vec![None; n];
+        let mut depth = vec![0; n];
+        let mut visited = vec![false; n];
+        for root in g.vertices_iter() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut queue = VecDeque::new();
+            queue.push_back(root);
+            while let Some(u) = queue.pop_front() {
+                for (&v, &e) in g.adjacent_edges_by_vertex_iter(u) {
+                    if !visited[v] {
+                        visited[v] = true;
+                        parent[v] = Some((u, g.main_edge(e)));
+                        depth[v] = depth[u] + 1;
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+
+        let is_tree_edge = |e: usize, w: usize| parent[w].map_or(false, |(_, f)| f == e);
+        let mut cycles = Vec::new();
+        for e in g.edges_iter() {
+            let (u, v) = match g.get_vertices_from_edge(e) {
+                Some(uv) => uv,
+                None => continue,
+            };
+            if is_tree_edge(e, u) || is_tree_edge(e, v) {
+                continue;
+            }
+            let (mut a, mut b) = (u, v);
+            let mut a_side = vec![a];
+            let mut b_side = Vec::new();
+            while a != b {
+                if depth[a] >= depth[b] {
+                    a = parent[a].expect("not a root").0;
+                    a_side.push(a);
+                } else {
+                    b_side.push(b);
+                    b = parent[b].expect("not a root").0;
+                }
+            }
+            b_side.reverse();
+            a_side.extend(b_side);
+            cycles.push(a_side);
+        }
+        cycles
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::Graph;
+    use graph::GraphBuilder;
+    use graph::UndirectedSimpleGraphImpl;
+    use graph::examples::graph1;
+
+    use super::*;
+
+    #[test]
+    fn test_simple_cycles() {
+        let g = graph1::<DirectedSimpleGraphImpl>();
+        let cycles: Vec<Vec<usize>> = DirectedCyclesBrowser::new(&g).simple_cycles_iter(None).collect();
+        assert_eq!(vec![vec![3, 4, 5]], cycles);
+    }
+
+    #[test]
+    fn test_simple_cycles_complete() {
+        let mut g: DirectedSimpleGraphImpl = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..4 {
+            g.create_vertex();
+        }
+        for u in 0..4 {
+            for v in 0..4 {
+                if u != v {
+                    g.add_edge(u, v);
+                }
+            }
+        }
+        let b = DirectedCyclesBrowser::new(&g);
+        // 6 of length 2, 8 of length 3, 6 of length 4
+        let mut cycles: Vec<Vec<usize>> = b.simple_cycles_iter(None).collect();
+        assert_eq!(20, cycles.len());
+        cycles.sort();
+        cycles.dedup();
+        assert_eq!(20, cycles.len());
+        assert_eq!(14, b.simple_cycles_iter(Some(3)).count());
+        assert_eq!(6, b.simple_cycles_iter(Some(2)).count());
+        assert_eq!(Some(vec![0, 1]), b.simple_cycles_iter(None).next());
+    }
+
+    #[test]
+    fn test_cycle_basis() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let cycles = UndirectedCyclesBrowser::new(&g).cycle_basis();
+        assert_eq!(3, cycles.len());
+        for cycle in cycles {
+            let mut sorted = cycle.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(cycle.len(), sorted.len());
+            for i in 0..cycle.len() {
+                let (u, v) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+                assert!(g.get_edges_from_vertices_iter(u, v).next().is_some());
+            }
+        }
+    }
+}
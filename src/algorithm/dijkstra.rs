@@ -28,57 +28,81 @@ use graph::DecoratedGraph;
 use graph::Graph;
 use algorithm::single_source_shortest_paths::SingleSourceShortestPathsImpl;
 
+/// An entry of the heap: the best known distance to a vertex. The heap pops the smallest
+/// distance first, or the greatest width first for a widest path.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct MinDistTo {
-    min_dist: usize,
+struct BestDistTo {
+    dist: usize,
     to: usize,
+    widest: bool,
 }
 
-impl Ord for MinDistTo {
-    fn cmp(&self, other: &MinDistTo) -> Ordering {
-        other.min_dist.cmp(&self.min_dist)
+impl Ord for BestDistTo {
+    fn cmp(&self, other: &BestDistTo) -> Ordering {
+        if self.widest {
+            self.dist.cmp(&other.dist)
+        } else {
+            other.dist.cmp(&self.dist)
+        }
     }
 }
 
-impl PartialOrd for MinDistTo {
-    fn partial_cmp(&self, other: &MinDistTo) -> Option<Ordering> {
+impl PartialOrd for BestDistTo {
+    fn partial_cmp(&self, other: &BestDistTo) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-pub struct DijkstraBrowser<'a, G, V, W>
+/// Shortest paths from a source, the length of an edge being its value. The `widest` variant
+/// computes the widest (bottleneck) paths instead: the "distance" of a vertex is then the
+/// greatest capacity of a path, i.e. the minimum value of its edges (`usize::MAX` for the source).
+pub struct DijkstraBrowser<'a, 'b, G, V, W>
     where G: 'a + Graph<'a> + DecoratedGraph<'a, V, &'a usize>,
           V: 'a + PartialEq + Clone + Debug,
-          W: 'a + Visitor
+          W: 'b + Visitor
 {
     decorated_graph: &'a G,
-    heap: BinaryHeap<MinDistTo>,
+    heap: BinaryHeap<BestDistTo>,
     black: Vec<bool>,
     dist: Vec<Option<usize>>,
     previous: Vec<Option<usize>>,
     source: usize,
     target: usize,
-    visitor: &'a mut W,
+    visitor: &'b mut W,
+    widest: bool,
     phantom_v: PhantomData<V>,
 }
 
-impl<'a, G, V, W> DijkstraBrowser<'a, G, V, W>
+impl<'a, 'b, G, V, W> DijkstraBrowser<'a, 'b, G, V, W>
     where G: 'a + Graph<'a> + DecoratedGraph<'a, V, &'a usize>,
           V: 'a + PartialEq + Clone + Debug,
-          W: 'a + Visitor
+          W: 'b + Visitor
 {
-    pub fn new(decorated_graph: &'a G, source: usize, target: usize, visitor: &'a mut W) -> DijkstraBrowser<'a, G, V, W> {
+    pub fn new(decorated_graph: &'a G, source: usize, target: usize, visitor: &'b mut W) -> DijkstraBrowser<'a, 'b, G, V, W> {
+        Self::with_kind(decorated_graph, source, target, visitor, false)
+    }
+
+    /// Maximize the minimum edge value along the path
+    pub fn widest(decorated_graph: &'a G, source: usize, target: usize, visitor: &'b mut W) -> DijkstraBrowser<'a, 'b, G, V, W> {
+        Self::with_kind(decorated_graph, source, target, visitor, true)
+    }
+
+    fn with_kind(decorated_graph: &'a G, source: usize, target: usize, visitor: &'b mut W, widest: bool) -> DijkstraBrowser<'a, 'b, G, V, W> {
+        let source_dist = if widest { usize::MAX } else { 0 };
         let mut heap = BinaryHeap::new();
-        heap.push(MinDistTo { min_dist: 0, to: source });
+        heap.push(BestDistTo { dist: source_dist, to: source, widest });
+        let mut dist = vec![None; decorated_graph.vertices_max()];
+        dist[source] = Some(source_dist);
         DijkstraBrowser {
             decorated_graph,
             heap,
             black: vec![false; decorated_graph.vertices_max()],
-            dist: vec![None; decorated_graph.vertices_max()],
+            dist,
             previous: vec![None; decorated_graph.vertices_max()],
             source,
             target,
             visitor,
+            widest,
             phantom_v: PhantomData,
         }
     }
@@ -89,12 +113,12 @@ impl<'a, G, V, W> DijkstraBrowser<'a, G, V, W>
                 None => {
                     break;
                 }
-                Some(MinDistTo { min_dist: _, to: node }) if self.black[node] => {}
-                Some(MinDistTo { min_dist: _, to: node }) if node == self.target => {
+                Some(BestDistTo { to: node, .. }) if self.black[node] => {}
+                Some(BestDistTo { to: node, .. }) if node == self.target => {
                     self.visitor.visit(node, None);
                     break;
                 }
-                Some(MinDistTo { min_dist: dist_cur_node, to: cur_node }) => { self.process(dist_cur_node, cur_node); }
+                Some(BestDistTo { dist: dist_cur_node, to: cur_node, .. }) => { self.process(dist_cur_node, cur_node); }
             }
         }
         SingleSourceShortestPathsImpl::new(self.source, &self.dist, &self.previous, false)
@@ -106,15 +130,19 @@ impl<'a, G, V, W> DijkstraBrowser<'a, G, V, W>
         for neighbor in self.decorated_graph.adjacent_vertices_iter(node) {
             for (_, o_weight) in self.decorated_graph.edges_values_iter(node, neighbor) {
                 let weight = *o_weight.unwrap_or(&0);
-                let dist_neighbor = dist_node + weight;
+                let dist_neighbor = if self.widest {
+                    dist_node.min(weight)
+                } else {
+                    dist_node + weight
+                };
                 if let Some(d) = self.dist[neighbor] {
-                    if d <= dist_neighbor { // not interesting
+                    if self.widest && d >= dist_neighbor || !self.widest && d <= dist_neighbor { // not interesting
                         continue;
                     }
                 }
                 self.dist[neighbor] = Some(dist_neighbor);
                 self.previous[neighbor] = Some(node);
-                self.heap.push(MinDistTo { min_dist: dist_neighbor, to: neighbor });
+                self.heap.push(BestDistTo { dist: dist_neighbor, to: neighbor, widest: self.widest });
             }
         }
     }
@@ -138,6 +166,21 @@ mod test {
         dijkstra(0, 5);
     }
 
+    #[test]
+    fn test_widest() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let dg = decorated_graph1(&mut g);
+        let mut visitor: Vec<Vec<usize>> = Vec::new();
+        let mut b = DijkstraBrowser::widest(&dg, 0, dg.vertices_max(), &mut visitor);
+        let paths = b.browse();
+        // A-F (13) is wider than A-B-F (1) or A-C-D-E-F (1)
+        assert_eq!(Some(13), paths.dist(5));
+        assert_eq!(vec![0, 5], paths.path(5));
+        assert_eq!(Some(1), paths.dist(4));
+        assert_eq!(Some(usize::MAX), paths.dist(0));
+        assert_eq!(None, paths.dist(7));
+    }
+
     fn dijkstra(source: usize, dest: usize) {
         let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
         {
@@ -0,0 +1,315 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::basic_graph::BasicGraph;
+use graph::DirectedGraph;
+use graph::GraphBuilder;
+
+/// Dominators of a flow graph from an entry vertex (Cooper, Harvey and Kennedy, "A Simple, Fast
+/// Dominance Algorithm").
+///
+/// The immediate dominators are computed by iterating over the vertices in reverse postorder
+/// until a fixed point is reached. Vertices that are not reachable from the entry have no
+/// dominator. For post-dominators, use a `ReversedGraph` and the exit vertex as entry.
+pub struct DominatorsBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    g: &'a G,
+    entry: usize,
+    // idom[entry] == Some(entry)
+    idom: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    frontiers: Vec<Vec<usize>>,
+}
+
+impl<'a, G> DominatorsBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    pub fn new(g: &'a G, entry: usize) -> DominatorsBrowser<'a, G> {
+        let n = g.vertices_max();
+        let mut b = DominatorsBrowser {
+            g,
+            entry,
+            idom: vec![None; n],
+            children: vec![Vec::new(); n],
+            frontiers: vec![Vec::new(); n],
+        };
+        b.browse();
+        b
+    }
+
+    fn browse(&mut self) {
+        let g = self.g;
+        let n = g.vertices_max();
+        let postorder = self.postorder();
+        let mut number: Vec<Option<usize>> = vec![None; n];
+        for (i, &u) in postorder.iter().enumerate() {
+            number[u] = Some(i);
+        }
+        let mut predecessors = vec![Vec::new(); n];
+        for &v in &postorder {
+            predecessors[v].extend(g.in_neighbors_iter(v).filter(|&u| number[u].is_some()));
+        }
+
+        let idom = &mut self.idom;
+        idom[self.entry] = Some(self.entry);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &u in postorder.iter().rev().skip(1) {
+                let mut new_idom = None;
+                for &p in &predecessors[u] {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = match new_idom {
+                        None => Some(p),
+                        Some(d) => Some(intersect(idom, &number, p, d)),
+                    };
+                }
+                if new_idom.is_some() && idom[u] != new_idom {
+                    idom[u] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        for &u in &postorder {
+            if u != self.entry {
+                if let Some(d) = idom[u] {
+                    self.children[d].push(u);
+                }
+            }
+            // the entry has a virtual predecessor: it is a join point as soon as it has a real
+            // one, and the runners walk up through it
+            let virtual_predecessors = if u == self.entry { 1 } else { 0 };
+            if predecessors[u].len() + virtual_predecessors < 2 {
+                continue;
+            }
+            let d = if u == self.entry { None } else { idom[u] };
+            for &p in &predecessors[u] {
+                let mut runner = Some(p);
+                while runner != d {
+                    let r = runner.expect("reachable");
+                    self.frontiers[r].push(u);
+                    runner = if r == self.entry { None } else { idom[r] };
+                }
+            }
+        }
+        for frontier in self.frontiers.iter_mut() {
+            frontier.sort();
+            frontier.dedup();
+        }
+        for children in self.children.iter_mut() {
+            children.sort();
+        }
+    }
+
+    fn postorder(&self) -> Vec<usize> {
+        let g = self.g;
+        let mut visited = vec![false; g.vertices_max()];
+        let mut postorder = Vec::with_capacity(g.vertices_size());
+        let mut stack = vec![(self.entry, g.adjacent_vertices_iter(self.entry))];
+        visited[self.entry] = true;
+        while let Some(&mut (u, ref mut it)) = stack.last_mut() {
+            match it.find(|&v| !visited[v]) {
+                Some(v) => {
+                    visited[v] = true;
+                    stack.push((v, g.adjacent_vertices_iter(v)));
+                }
+                None => {
+                    postorder.push(u);
+                    stack.pop();
+                }
+            }
+        }
+        postorder
+    }
+
+    /// The entry vertex
+    pub fn entry(&self) -> usize {
+        self.entry
+    }
+
+    /// The immediate dominator of u, or None if u is the entry or is not reachable
+    pub fn immediate_dominator(&self, u: usize) -> Option<usize> {
+        if u == self.entry {
+            None
+        } else {
+            self.idom[u]
+        }
+    }
+
+    /// true if u is reachable from the entry
+    pub fn is_reachable(&self, u: usize) -> bool {
+        self.idom[u].is_some()
+    }
+
+    /// The dominators of u, from u to the entry. Empty if u is not reachable.
+    pub fn dominators(&self, u: usize) -> Vec<usize> {
+        let mut dominators = Vec::new();
+        if self.idom[u].is_none() {
+            return dominators;
+        }
+        let mut w = u;
+        dominators.push(w);
+        while let Some(d) = self.immediate_dominator(w) {
+            dominators.push(d);
+            w = d;
+        }
+        dominators
+    }
+
+    /// true if every path from the entry to v goes through u (a vertex dominates itself)
+    pub fn dominates(&self, u: usize, v: usize) -> bool {
+        if self.idom[u].is_none() {
+            return false;
+        }
+        let mut w = v;
+        loop {
+            if w == u {
+                return true;
+            }
+            match self.immediate_dominator(w) {
+                Some(d) => w = d,
+                None => return false,
+            }
+        }
+    }
+
+    /// The vertices immediately dominated by u
+    pub fn children(&self, u: usize) -> &[usize] {
+        &self.children[u]
+    }
+
+    /// The dominance frontier of u: the vertices v such that u dominates a predecessor of v,
+    /// but does not strictly dominate v
+    pub fn dominance_frontier(&self, u: usize) -> &[usize] {
+        &self.frontiers[u]
+    }
+
+    /// The dominator tree, with an edge idom(u) -> u for every reachable vertex u. The vertices
+    /// keep their ids; the vertices that are not reachable are removed.
+    pub fn dominator_tree<'b, T>(&self) -> T
+        where T: GraphBuilder<'b>
+    {
+        let mut tree: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..self.g.vertices_max() {
+            tree.create_vertex();
+        }
+        for u in (0..self.g.vertices_max()).rev() {
+            if self.idom[u].is_none() {
+                tree.remove_vertex(u);
+            }
+        }
+        for (u, children) in self.children.iter().enumerate() {
+            for &v in children {
+                tree.add_edge(u, v);
+            }
+        }
+        tree
+    }
+}
+
+fn intersect(idom: &[Option<usize>], number: &[Option<usize>], u: usize, v: usize) -> usize {
+    let (mut f1, mut f2) = (u, v);
+    while f1 != f2 {
+        while number[f1] < number[f2] {
+            f1 = idom[f1].expect("processed");
+        }
+        while number[f2] < number[f1] {
+            f2 = idom[f2].expect("processed");
+        }
+    }
+    f1
+}
+
+#[cfg(test)]
+mod test {
+    use graph::DirectedSimpleGraphImpl;
+    use graph::Graph;
+    use graph::ReversedGraph;
+
+    use super::*;
+
+    fn cfg() -> DirectedSimpleGraphImpl {
+        let mut g: DirectedSimpleGraphImpl = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..7 {
+            g.create_vertex();
+        }
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(1, 3);
+        g.add_edge(2, 4);
+        g.add_edge(3, 4);
+        g.add_edge(4, 1);
+        g.add_edge(4, 5);
+        g
+    }
+
+    #[test]
+    fn test_dominators() {
+        let g = cfg();
+        let b = DominatorsBrowser::new(&g, 0);
+        let idoms: Vec<Option<usize>> = (0..7).map(|u| b.immediate_dominator(u)).collect();
+        assert_eq!(vec![None, Some(0), Some(1), Some(1), Some(1), Some(4), None], idoms);
+        assert_eq!(vec![5, 4, 1, 0], b.dominators(5));
+        assert!(b.dominates(1, 5));
+        assert!(!b.dominates(2, 4));
+        assert!(!b.is_reachable(6));
+        assert_eq!(&[2, 3, 4], b.children(1));
+        let tree: DirectedSimpleGraphImpl = b.dominator_tree();
+        assert_eq!(6, tree.vertices_size());
+        assert_eq!(5, tree.edges_size());
+    }
+
+    #[test]
+    fn test_dominance_frontiers() {
+        let g = cfg();
+        let b = DominatorsBrowser::new(&g, 0);
+        let frontiers: Vec<&[usize]> = (0..6).map(|u| b.dominance_frontier(u)).collect();
+        let expected: Vec<&[usize]> = vec![&[], &[1], &[4], &[4], &[1], &[]];
+        assert_eq!(expected, frontiers);
+    }
+
+    #[test]
+    fn test_entry_is_a_loop_header() {
+        let mut g: DirectedSimpleGraphImpl = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..3 {
+            g.create_vertex();
+        }
+        g.add_edge(0, 1);
+        g.add_edge(1, 0);
+        g.add_edge(1, 2);
+        let b = DominatorsBrowser::new(&g, 0);
+        assert_eq!(&[0], b.dominance_frontier(0));
+        assert_eq!(&[0], b.dominance_frontier(1));
+        assert!(b.dominance_frontier(2).is_empty());
+        assert_eq!(Some(1), b.immediate_dominator(2));
+    }
+
+    #[test]
+    fn test_post_dominators() {
+        let g = cfg();
+        let r = ReversedGraph::new(&g);
+        let b = DominatorsBrowser::new(&r, 5);
+        let ipdoms: Vec<Option<usize>> = (0..7).map(|u| b.immediate_dominator(u)).collect();
+        assert_eq!(vec![Some(1), Some(4), Some(4), Some(4), Some(5), None, None], ipdoms);
+    }
+}
@@ -0,0 +1,231 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::fmt::Debug;
+
+use algorithm::bfs::BFSBrowser;
+use algorithm::dijkstra::DijkstraBrowser;
+use algorithm::single_source_shortest_paths::SingleSourceShortestPaths;
+use algorithm::visitor::Visitor;
+use graph::DecoratedGraph;
+use graph::Graph;
+
+/// A visitor for a BFS: the distance of a vertex is the distance of its parent plus one.
+struct DistanceVisitor {
+    dist: Vec<Option<usize>>,
+}
+
+impl Visitor for DistanceVisitor {
+    fn visit(&mut self, u: usize, parent: Option<usize>) {
+        self.dist[u] = match parent {
+            None => Some(0),
+            Some(p) => self.dist[p].map(|d| d + 1),
+        };
+    }
+}
+
+struct NoVisitor;
+
+impl Visitor for NoVisitor {}
+
+/// Distance metrics: the eccentricity of a vertex is its greatest distance to another vertex.
+/// If some vertex is not reachable from u, the eccentricity of u is infinite (`None`), and so is
+/// the diameter.
+pub struct EccentricityBrowser<'a, G>
+    where G: 'a + Graph<'a>
+{
+    g: &'a G,
+    eccentricities: Vec<Option<usize>>,
+}
+
+impl<'a, G> EccentricityBrowser<'a, G>
+    where G: 'a + Graph<'a>
+{
+    /// Every edge has length 1: a BFS from every vertex
+    pub fn new(g: &'a G) -> EccentricityBrowser<'a, G> {
+        let mut eccentricities = vec![None; g.vertices_max()];
+        for u in g.vertices_iter() {
+            let dist = Self::bfs_dist(g, u);
+            eccentricities[u] = Self::max_dist(g, u, |v| dist[v]);
+        }
+        EccentricityBrowser {
+            g,
+            eccentricities,
+        }
+    }
+
+    /// The length of an edge is its value: Dijkstra from every vertex
+    pub fn weighted<V>(g: &'a G) -> EccentricityBrowser<'a, G>
+        where G: DecoratedGraph<'a, V, &'a usize>,
+              V: 'a + PartialEq + Clone + Debug
+    {
+        let mut eccentricities = vec![None; g.vertices_max()];
+        for u in g.vertices_iter() {
+            let mut visitor = NoVisitor;
+            // vertices_max() is not a vertex: Dijkstra browses the whole graph
+            let mut b = DijkstraBrowser::new(g, u, g.vertices_max(), &mut visitor);
+            let paths = b.browse();
+            eccentricities[u] = Self::max_dist(g, u, |v| paths.dist(v));
+        }
+        EccentricityBrowser {
+            g,
+            eccentricities,
+        }
+    }
+
+    fn bfs_dist(g: &'a G, source: usize) -> Vec<Option<usize>> {
+        let mut visitor = DistanceVisitor { dist: vec![None; g.vertices_max()] };
+        BFSBrowser::new(g, &mut visitor).browse_from(source);
+        visitor.dist
+    }
+
+    /// return the greatest distance from u to the other vertices, if all are reachable
+    fn max_dist<F>(g: &'a G, u: usize, dist: F) -> Option<usize>
+        where F: Fn(usize) -> Option<usize>
+    {
+        let mut max = 0;
+        for v in g.vertices_iter().filter(|&v| v != u) {
+            match dist(v) {
+                None => { return None; }
+                Some(d) if d > max => { max = d; }
+                _ => {}
+            }
+        }
+        Some(max)
+    }
+
+    pub fn eccentricity(&self, u: usize) -> Option<usize> {
+        self.eccentricities[u]
+    }
+
+    /// return the greatest eccentricity
+    pub fn diameter(&self) -> Option<usize> {
+        let mut diameter = 0;
+        for u in self.g.vertices_iter() {
+            match self.eccentricities[u] {
+                None => { return None; }
+                Some(e) if e > diameter => { diameter = e; }
+                _ => {}
+            }
+        }
+        Some(diameter)
+    }
+
+    /// return the smallest eccentricity
+    pub fn radius(&self) -> Option<usize> {
+        self.g.vertices_iter().filter_map(|u| self.eccentricities[u]).min()
+    }
+
+    /// return the vertices whose eccentricity is the radius
+    pub fn center(&self) -> Vec<usize> {
+        match self.radius() {
+            None => vec![],
+            radius => self.g.vertices_iter().filter(|&u| self.eccentricities[u] == radius).collect(),
+        }
+    }
+
+    /// return the vertices whose eccentricity is the diameter
+    pub fn periphery(&self) -> Vec<usize> {
+        match self.diameter() {
+            None => vec![],
+            diameter => self.g.vertices_iter().filter(|&u| self.eccentricities[u] == diameter).collect(),
+        }
+    }
+
+    /// A lower bound of the diameter of the component of `start`, with two BFS only: the
+    /// eccentricity of the vertex that is the farthest from `start`. It is exact on trees and
+    /// often close on real graphs.
+    pub fn double_sweep_diameter(g: &'a G, start: usize) -> usize {
+        let farthest = |dist: &[Option<usize>]| dist.iter().enumerate()
+            .filter_map(|(v, &d)| d.map(|d| (d, v))).max().unwrap_or((0, start));
+        let (_, a) = farthest(&Self::bfs_dist(g, start));
+        let (d, _) = farthest(&Self::bfs_dist(g, a));
+        d
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::examples::decorated_graph1;
+    use graph::examples::graph2;
+    use graph::GraphBuilder;
+    use graph::GraphDecorator;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    fn path(n: usize) -> UndirectedSimpleGraphImpl {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        for u in 1..n {
+            g.add_edge(u - 1, u);
+        }
+        g
+    }
+
+    #[test]
+    fn test_path() {
+        let g = path(5);
+        let b = EccentricityBrowser::new(&g);
+        assert_eq!(Some(4), b.eccentricity(0));
+        assert_eq!(Some(2), b.eccentricity(2));
+        assert_eq!(Some(4), b.diameter());
+        assert_eq!(Some(2), b.radius());
+        assert_eq!(vec![2], b.center());
+        assert_eq!(vec![0, 4], b.periphery());
+        assert_eq!(4, EccentricityBrowser::double_sweep_diameter(&g, 2));
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let g = graph2::<UndirectedSimpleGraphImpl>();
+        let b = EccentricityBrowser::new(&g);
+        assert_eq!(None, b.diameter());
+        assert_eq!(None, b.radius());
+        assert!(b.center().is_empty());
+        assert!(EccentricityBrowser::double_sweep_diameter(&g, 0) > 0);
+    }
+
+    #[test]
+    fn test_weighted() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let dg = decorated_graph1(&mut g);
+        let b = EccentricityBrowser::weighted(&dg);
+        assert_eq!(None, b.eccentricity(0));
+
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut dg = GraphDecorator::new(&mut g);
+        for c in "ABC".chars() {
+            dg.add_vertex(c);
+        }
+        dg.add_edge(0, 1, 2);
+        dg.add_edge(1, 2, 3);
+        dg.add_edge(0, 2, 10);
+        let b = EccentricityBrowser::weighted(&dg);
+        assert_eq!(Some(5), b.eccentricity(0));
+        assert_eq!(Some(3), b.eccentricity(1));
+        assert_eq!(Some(5), b.diameter());
+        assert_eq!(vec![1], b.center());
+        assert_eq!(vec![0, 2], b.periphery());
+    }
+}
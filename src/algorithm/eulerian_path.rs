@@ -0,0 +1,282 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::DirectedGraph;
+use graph::UndirectedGraph;
+
+/// Hierholzer's algorithm on adjacency lists (vertex, edge). When an edge is consumed, its twin
+/// (if any) is consumed too. Return None if some edges are not reachable from `start`.
+fn hierholzer<F>(adjacent: &[Vec<(usize, usize)>], start: usize, edges_count: usize, edges_max: usize,
+                 twin: F) -> Option<Vec<usize>>
+    where F: Fn(usize) -> Option<usize>
+{
+    let mut used = vec![false; edges_max];
+    let mut next = vec![0; adjacent.len()];
+    let mut stack: Vec<(usize, Option<usize>)> = vec![(start, None)];
+    let mut path = Vec::with_capacity(edges_count);
+    while let Some(&(u, _)) = stack.last() {
+        while next[u] < adjacent[u].len() && used[adjacent[u][next[u]].1] {
+            next[u] += 1;
+        }
+        if next[u] < adjacent[u].len() {
+            let (v, e) = adjacent[u][next[u]];
+            used[e] = true;
+            if let Some(e2) = twin(e) {
+                used[e2] = true;
+            }
+            stack.push((v, Some(e)));
+        } else if let Some((_, Some(e))) = stack.pop() {
+            path.push(e);
+        }
+    }
+    if path.len() != edges_count {
+        return None;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Eulerian path and circuit in a directed graph. Paths are sequences of edges.
+pub struct DirectedEulerianBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    g: &'a G,
+    adjacent: Vec<Vec<(usize, usize)>>,
+    in_degree: Vec<usize>,
+}
+
+impl<'a, G> DirectedEulerianBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> DirectedEulerianBrowser<'a, G> {
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        let mut in_degree = vec![0; g.vertices_max()];
+        for u in g.vertices_iter() {
+            for (&v, &e) in g.adjacent_edges_by_vertex_iter(u) {
+                adjacent[u].push((v, e));
+                in_degree[v] += 1;
+            }
+        }
+        DirectedEulerianBrowser {
+            g,
+            adjacent,
+            in_degree,
+        }
+    }
+
+    pub fn has_eulerian_path(&self) -> bool {
+        self.eulerian_path().is_some()
+    }
+
+    pub fn has_eulerian_circuit(&self) -> bool {
+        self.eulerian_circuit().is_some()
+    }
+
+    /// return the edges of an Eulerian path, if any
+    pub fn eulerian_path(&self) -> Option<Vec<usize>> {
+        self.browse(false)
+    }
+
+    /// return the edges of an Eulerian circuit, if any
+    pub fn eulerian_circuit(&self) -> Option<Vec<usize>> {
+        self.browse(true)
+    }
+
+    fn browse(&self, circuit: bool) -> Option<Vec<usize>> {
+        let mut start = None;
+        let mut first = None;
+        let mut end_count = 0;
+        for u in self.g.vertices_iter() {
+            let (out_u, in_u) = (self.adjacent[u].len(), self.in_degree[u]);
+            if out_u == in_u + 1 && start.is_none() {
+                start = Some(u);
+            } else if in_u == out_u + 1 && end_count == 0 {
+                end_count += 1;
+            } else if in_u != out_u {
+                return None;
+            }
+            if out_u > 0 && first.is_none() {
+                first = Some(u);
+            }
+        }
+        if start.is_some() != (end_count == 1) || (circuit && start.is_some()) {
+            return None;
+        }
+        match start.or(first) {
+            None => Some(vec![]),
+            Some(s) => hierholzer(&self.adjacent, s, self.g.edges_size(), self.g.edges_max(), |_| None),
+        }
+    }
+}
+
+/// Eulerian path and circuit in an undirected graph. Paths are sequences of edges, where each
+/// edge is the twin that is oriented in the direction of the path: every undirected edge appears
+/// once.
+pub struct UndirectedEulerianBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    adjacent: Vec<Vec<(usize, usize)>>,
+}
+
+impl<'a, G> UndirectedEulerianBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> UndirectedEulerianBrowser<'a, G> {
+        let mut adjacent = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            adjacent[u].extend(g.adjacent_edges_by_vertex_iter(u).map(|(&v, &e)| (v, e)));
+        }
+        UndirectedEulerianBrowser {
+            g,
+            adjacent,
+        }
+    }
+
+    pub fn has_eulerian_path(&self) -> bool {
+        self.eulerian_path().is_some()
+    }
+
+    pub fn has_eulerian_circuit(&self) -> bool {
+        self.eulerian_circuit().is_some()
+    }
+
+    /// return the edges of an Eulerian path, if any
+    pub fn eulerian_path(&self) -> Option<Vec<usize>> {
+        self.browse(false)
+    }
+
+    /// return the edges of an Eulerian circuit, if any
+    pub fn eulerian_circuit(&self) -> Option<Vec<usize>> {
+        self.browse(true)
+    }
+
+    fn browse(&self, circuit: bool) -> Option<Vec<usize>> {
+        let mut odd_vertices = self.g.vertices_iter().filter(|&u| self.g.degree(u) % 2 == 1);
+        let start = match (odd_vertices.next(), odd_vertices.count()) {
+            (None, _) => self.g.vertices_iter().find(|&u| !self.adjacent[u].is_empty()),
+            (Some(u), 1) if !circuit => Some(u),
+            _ => { return None; }
+        };
+        match start {
+            None => Some(vec![]),
+            Some(s) => hierholzer(&self.adjacent, s, self.g.edges_iter().count(), self.g.edges_max(),
+                                  |e| self.g.get_reversed_edge(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::Graph;
+    use graph::GraphBuilder;
+    use graph::UndirectedSimpleGraphImpl;
+    use graph::examples::graph1;
+
+    use super::*;
+
+    fn build<'a, T>(n: usize, edges: &[(usize, usize)]) -> T
+        where T: Graph<'a> + GraphBuilder<'a>
+    {
+        let mut g: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        for &(u, v) in edges {
+            g.add_edge(u, v);
+        }
+        g
+    }
+
+    fn assert_is_path<'a, G: Graph<'a>>(g: &G, path: &[usize]) {
+        let vertices: Vec<(usize, usize)> = path.iter()
+            .map(|&e| g.get_vertices_from_edge(e).expect("an edge")).collect();
+        for w in vertices.windows(2) {
+            assert_eq!(w[0].1, w[1].0);
+        }
+    }
+
+    #[test]
+    fn test_directed() {
+        let g: DirectedSimpleGraphImpl = build(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 2)]);
+        let b = DirectedEulerianBrowser::new(&g);
+        let circuit = b.eulerian_circuit().expect("a circuit");
+        assert_eq!(5, circuit.len());
+        assert_is_path(&g, &circuit);
+
+        let g: DirectedSimpleGraphImpl = build(4, &[(0, 1), (1, 2), (2, 0), (2, 3)]);
+        let b = DirectedEulerianBrowser::new(&g);
+        assert!(!b.has_eulerian_circuit());
+        let path = b.eulerian_path().expect("a path");
+        assert_eq!(Some((2, 0)), g.get_vertices_from_edge(path[0]));
+        assert_eq!(Some((2, 3)), g.get_vertices_from_edge(path[3]));
+        assert_is_path(&g, &path);
+    }
+
+    #[test]
+    fn test_directed_disconnected() {
+        let g: DirectedSimpleGraphImpl = build(4, &[(0, 1), (1, 0), (2, 3), (3, 2)]);
+        let b = DirectedEulerianBrowser::new(&g);
+        assert!(!b.has_eulerian_path());
+    }
+
+    #[test]
+    fn test_undirected() {
+        // a house: a square and a roof
+        let g: UndirectedSimpleGraphImpl = build(5, &[(0, 1), (1, 2), (2, 3), (3, 0), (2, 4), (4, 3)]);
+        let b = UndirectedEulerianBrowser::new(&g);
+        assert!(!b.has_eulerian_circuit());
+        let path = b.eulerian_path().expect("a path");
+        assert_eq!(6, path.len());
+        assert_is_path(&g, &path);
+        let edges: HashSet<usize> = path.iter().map(|&e| g.main_edge(e)).collect();
+        assert_eq!(6, edges.len());
+        let (start, _) = g.get_vertices_from_edge(path[0]).expect("an edge");
+        assert!(start == 2 || start == 3);
+    }
+
+    #[test]
+    fn test_undirected_circuit() {
+        let g: UndirectedSimpleGraphImpl = build(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+        let b = UndirectedEulerianBrowser::new(&g);
+        let circuit = b.eulerian_circuit().expect("a circuit");
+        assert_eq!(6, circuit.len());
+        assert_is_path(&g, &circuit);
+        let (start, _) = g.get_vertices_from_edge(circuit[0]).expect("an edge");
+        let (_, end) = g.get_vertices_from_edge(circuit[5]).expect("an edge");
+        assert_eq!(start, end);
+
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        assert!(!UndirectedEulerianBrowser::new(&g).has_eulerian_path());
+    }
+
+    #[test]
+    fn test_undirected_loop() {
+        // a loop adds 2 to the degree: the triangle with a loop still has a circuit
+        let g: UndirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2), (2, 0), (1, 1)]);
+        let b = UndirectedEulerianBrowser::new(&g);
+        let circuit = b.eulerian_circuit().expect("a circuit");
+        assert_eq!(4, circuit.len());
+        assert_is_path(&g, &circuit);
+    }
+}
@@ -21,7 +21,7 @@ use std::marker::PhantomData;
 /// along with this program.  If not, see <http://www.gnu.org/licenses/>.
 /// ***************************************************************************
 
-use graph::{DecoratedGraph, Graph};
+use graph::{DecoratedGraph, Graph, WeightMatrix};
 use algorithm::all_pairs_shortest_paths::AllPairsShortestPathsImpl;
 
 pub struct FloydWarshallBrowser<'a, G, V>
@@ -32,6 +32,7 @@ pub struct FloydWarshallBrowser<'a, G, V>
     dist: Vec<Option<usize>>,
     next: Vec<Option<usize>>,
     v_count: usize,
+    initialized: bool,
     phantom_v: PhantomData<V>,
 }
 
@@ -47,16 +48,37 @@ impl<'a, G, V> FloydWarshallBrowser<'a, G, V>
             dist: vec![None; v_count * v_count],
             next: vec![None; v_count * v_count],
             v_count,
+            initialized: false,
             phantom_v: PhantomData,
         }
     }
 
+    /// Initialize the distances directly from the weights of an adjacency matrix graph, without
+    /// browsing the edges
+    pub fn from_matrix(decorated_graph: &'a G) -> FloydWarshallBrowser<'a, G, V>
+        where G: WeightMatrix
+    {
+        let mut b = Self::new(decorated_graph);
+        for u in decorated_graph.vertices_iter() {
+            for v in decorated_graph.vertices_iter() {
+                if let Some(w) = decorated_graph.edge_weight(u, v) {
+                    b.dist[u * b.v_count + v] = Some(w);
+                    b.next[u * b.v_count + v] = Some(v);
+                }
+            }
+        }
+        b.initialized = true;
+        b
+    }
+
     pub fn browse(&mut self) -> AllPairsShortestPathsImpl {
-        for e in self.decorated_graph.edges_iter() {
-            let (u, v) = self.decorated_graph.get_vertices_from_edge(e).expect("");
-            let w = self.decorated_graph.edges_values_iter(u, v).map(|(_, o_weight)| *o_weight.unwrap_or(&0)).min().expect("Should not happen");
-            self.dist[u * self.v_count + v] = Some(w);
-            self.next[u * self.v_count + v] = Some(v);
+        if !self.initialized {
+            for e in self.decorated_graph.edges_iter() {
+                let (u, v) = self.decorated_graph.get_vertices_from_edge(e).expect("");
+                let w = self.decorated_graph.edges_values_iter(u, v).map(|(_, o_weight)| *o_weight.unwrap_or(&0)).min().expect("Should not happen");
+                self.dist[u * self.v_count + v] = Some(w);
+                self.next[u * self.v_count + v] = Some(v);
+            }
         }
         for u in self.decorated_graph.vertices_iter() {
             self.dist[u * self.v_count + u] = Some(0);
@@ -97,6 +119,7 @@ impl<'a, G, V> FloydWarshallBrowser<'a, G, V>
 #[cfg(test)]
 mod test {
     use graph::basic_graph::BasicGraph;
+    use graph::DirectedMatrixGraph;
     use graph::DirectedSimpleGraphImpl;
     use graph::examples::decorated_graph1;
     use graph::GraphBuilder;
@@ -156,6 +179,19 @@ mod test {
 
                 path.push(x.path(0, 5));
             }
+            {
+                let mut m = DirectedMatrixGraph::with_vertices(dg.vertices_max());
+                for e in dg.edges_iter() {
+                    let (u, v) = dg.get_vertices_from_edge(e).expect("an edge");
+                    let (_, o_weight) = dg.edges_values_iter(u, v).next().expect("an edge");
+                    m.add_weighted_edge(u, v, *o_weight.expect("a weight"));
+                }
+                let mut b = FloydWarshallBrowser::from_matrix(&m);
+                let y = b.browse();
+                assert_eq!(vec!(0, 2, 3, 4, 5), y.path(0, 5));
+                assert_eq!(Some(12), y.dist(1, 5));
+                assert_eq!(None, y.dist(5, 6));
+            }
             {
                 let h = GraphvizBuilderDirectedImpl::new(&dg, &path);
                 let gw = GraphvizWriter::new(&h);
@@ -0,0 +1,339 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::Graph;
+
+/// Scores computed by power iteration
+pub struct Ranking {
+    scores: Vec<f64>,
+    iterations: usize,
+    converged: bool,
+}
+
+impl Ranking {
+    pub fn score(&self, u: usize) -> f64 {
+        self.scores[u]
+    }
+
+    /// return the scores, indexed by vertex
+    pub fn scores(&self) -> &[f64] {
+        &self.scores
+    }
+
+    /// return the number of iterations performed
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// return false if the maximum number of iterations was reached before convergence
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+}
+
+/// Hub and authority scores computed by HITS. Both sum to 1.
+pub struct HubsAndAuthorities {
+    hubs: Ranking,
+    authorities: Ranking,
+}
+
+impl HubsAndAuthorities {
+    pub fn hubs(&self) -> &Ranking {
+        &self.hubs
+    }
+
+    pub fn authorities(&self) -> &Ranking {
+        &self.authorities
+    }
+}
+
+/// PageRank, eigenvector centrality and HITS, by power iteration. The iteration stops when the
+/// L1 distance between two successive score vectors is below `n * tolerance`, or after
+/// `max_iterations` iterations.
+pub struct LinkAnalysisBrowser<'a, G>
+    where G: 'a + Graph<'a>
+{
+    g: &'a G,
+    successors: Vec<Vec<usize>>,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+    personalization: Option<Vec<f64>>,
+    dangling: Option<Vec<f64>>,
+}
+
+impl<'a, G> LinkAnalysisBrowser<'a, G>
+    where G: 'a + Graph<'a>
+{
+    pub fn new(g: &'a G) -> LinkAnalysisBrowser<'a, G> {
+        let mut successors = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            successors[u].extend(g.adjacent_vertices_iter(u));
+        }
+        LinkAnalysisBrowser {
+            g,
+            successors,
+            damping: 0.85,
+            tolerance: 1e-6,
+            max_iterations: 100,
+            personalization: None,
+            dangling: None,
+        }
+    }
+
+    /// The probability to follow a link rather than to teleport (default: 0.85)
+    pub fn with_damping(mut self, damping: f64) -> LinkAnalysisBrowser<'a, G> {
+        self.damping = damping;
+        self
+    }
+
+    /// default: 1e-6
+    pub fn with_tolerance(mut self, tolerance: f64) -> LinkAnalysisBrowser<'a, G> {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// default: 100
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> LinkAnalysisBrowser<'a, G> {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// The teleport distribution of PageRank, indexed by vertex (default: uniform). The weights
+    /// are normalized.
+    pub fn with_personalization(mut self, personalization: Vec<f64>) -> LinkAnalysisBrowser<'a, G> {
+        self.personalization = Some(personalization);
+        self
+    }
+
+    /// How PageRank redistributes the score of the vertices without successors, indexed by vertex
+    /// (default: the personalization). The weights are normalized.
+    pub fn with_dangling(mut self, dangling: Vec<f64>) -> LinkAnalysisBrowser<'a, G> {
+        self.dangling = Some(dangling);
+        self
+    }
+
+    /// PageRank: the stationary distribution of a random surfer that follows a link with
+    /// probability `damping`, or teleports.
+    pub fn pagerank(&self) -> Ranking {
+        let teleport = self.distribution(&self.personalization);
+        let dangling = match self.dangling {
+            None => teleport.clone(),
+            Some(_) => self.distribution(&self.dangling),
+        };
+        let n = self.g.vertices_size() as f64;
+        let mut x = self.distribution(&None);
+        self.iterate(&mut x, |x| {
+            let mut next = vec![0.0; x.len()];
+            let mut dangling_sum = 0.0;
+            for u in self.g.vertices_iter() {
+                let successors = &self.successors[u];
+                if successors.is_empty() {
+                    dangling_sum += x[u];
+                } else {
+                    let share = x[u] / successors.len() as f64;
+                    for &v in successors {
+                        next[v] += self.damping * share;
+                    }
+                }
+            }
+            for u in self.g.vertices_iter() {
+                next[u] += self.damping * dangling_sum * dangling[u] + (1.0 - self.damping) * teleport[u];
+            }
+            next
+        }, n)
+    }
+
+    /// Eigenvector centrality: the score of a vertex is proportional to the sum of the scores of
+    /// its predecessors. The scores have a unit euclidean norm.
+    pub fn eigenvector_centrality(&self) -> Ranking {
+        let n = self.g.vertices_size() as f64;
+        let mut x = self.distribution(&None);
+        self.iterate(&mut x, |x| {
+            // iterate on A + I: same eigenvectors, but avoid oscillations on bipartite graphs
+            let mut next = x.to_vec();
+            for u in self.g.vertices_iter() {
+                for &v in &self.successors[u] {
+                    next[v] += x[u];
+                }
+            }
+            let norm = next.iter().map(|s| s * s).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                next.iter_mut().for_each(|s| *s /= norm);
+            }
+            next
+        }, n)
+    }
+
+    /// HITS: the authority score of a vertex is the sum of the hub scores of its predecessors,
+    /// and the hub score of a vertex is the sum of the authority scores of its successors.
+    pub fn hits(&self) -> HubsAndAuthorities {
+        let n = self.g.vertices_size() as f64;
+        let mut hubs = self.distribution(&None);
+        let hubs_ranking = self.iterate(&mut hubs, |h| {
+            let authorities = self.authorities_from_hubs(h);
+            let mut next = vec![0.0; h.len()];
+            for u in self.g.vertices_iter() {
+                next[u] = self.successors[u].iter().map(|&v| authorities[v]).sum();
+            }
+            Self::normalize(&mut next);
+            next
+        }, n);
+        let mut authorities = self.authorities_from_hubs(&hubs_ranking.scores);
+        Self::normalize(&mut authorities);
+        HubsAndAuthorities {
+            authorities: Ranking {
+                scores: authorities,
+                iterations: hubs_ranking.iterations,
+                converged: hubs_ranking.converged,
+            },
+            hubs: hubs_ranking,
+        }
+    }
+
+    fn authorities_from_hubs(&self, hubs: &[f64]) -> Vec<f64> {
+        let mut authorities = vec![0.0; hubs.len()];
+        for u in self.g.vertices_iter() {
+            for &v in &self.successors[u] {
+                authorities[v] += hubs[u];
+            }
+        }
+        authorities
+    }
+
+    /// Apply `step` until convergence
+    fn iterate<F>(&self, x: &mut Vec<f64>, step: F, n: f64) -> Ranking
+        where F: Fn(&[f64]) -> Vec<f64>
+    {
+        for iteration in 1..self.max_iterations + 1 {
+            let next = step(x);
+            let error: f64 = next.iter().zip(x.iter()).map(|(a, b)| (a - b).abs()).sum();
+            *x = next;
+            if error < n * self.tolerance {
+                return Ranking { scores: x.clone(), iterations: iteration, converged: true };
+            }
+        }
+        Ranking { scores: x.clone(), iterations: self.max_iterations, converged: false }
+    }
+
+    /// return the normalized weights, or the uniform distribution on vertices
+    fn distribution(&self, weights: &Option<Vec<f64>>) -> Vec<f64> {
+        let mut distribution = vec![0.0; self.g.vertices_max()];
+        for u in self.g.vertices_iter() {
+            distribution[u] = match *weights {
+                None => 1.0,
+                Some(ref w) => w.get(u).cloned().unwrap_or(0.0),
+            };
+        }
+        Self::normalize(&mut distribution);
+        distribution
+    }
+
+    fn normalize(x: &mut [f64]) {
+        let total: f64 = x.iter().sum();
+        if total > 0.0 {
+            x.iter_mut().for_each(|s| *s /= total);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::GraphBuilder;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    fn build<'a, T>(n: usize, edges: &[(usize, usize)]) -> T
+        where T: Graph<'a> + GraphBuilder<'a>
+    {
+        let mut g: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        for &(u, v) in edges {
+            g.add_edge(u, v);
+        }
+        g
+    }
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!((expected - actual).abs() < 1e-4, "{} != {}", expected, actual);
+    }
+
+    #[test]
+    fn test_pagerank() {
+        let g: DirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2), (2, 0), (0, 2)]);
+        let ranking = LinkAnalysisBrowser::new(&g).pagerank();
+        assert!(ranking.converged());
+        assert!(ranking.iterations() > 1);
+        assert_close(1.0, ranking.scores().iter().sum());
+        assert!(ranking.score(2) > ranking.score(0));
+        assert!(ranking.score(0) > ranking.score(1));
+
+        let g: DirectedSimpleGraphImpl = build(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let ranking = LinkAnalysisBrowser::new(&g).pagerank();
+        for u in 0..4 {
+            assert_close(0.25, ranking.score(u));
+        }
+    }
+
+    #[test]
+    fn test_pagerank_dangling_and_personalization() {
+        // 1 is dangling: its score goes back to 0
+        let g: DirectedSimpleGraphImpl = build(2, &[(0, 1)]);
+        let ranking = LinkAnalysisBrowser::new(&g).with_dangling(vec![1.0, 0.0]).pagerank();
+        assert_close(1.0, ranking.scores().iter().sum());
+        // x0 = 0.85 * x1 + 0.075, x1 = 0.85 * x0 + 0.075
+        assert_close(0.5, ranking.score(0));
+
+        let g: DirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2), (2, 0)]);
+        let ranking = LinkAnalysisBrowser::new(&g).with_damping(0.0)
+            .with_personalization(vec![2.0, 0.0, 0.0]).pagerank();
+        assert_eq!(&[1.0, 0.0, 0.0], ranking.scores());
+
+        let ranking = LinkAnalysisBrowser::new(&g).with_max_iterations(1).with_personalization(vec![1.0, 0.0, 0.0]).pagerank();
+        assert!(!ranking.converged());
+        assert_eq!(1, ranking.iterations());
+    }
+
+    #[test]
+    fn test_eigenvector_centrality() {
+        // a star
+        let g: UndirectedSimpleGraphImpl = build(4, &[(0, 1), (0, 2), (0, 3)]);
+        let ranking = LinkAnalysisBrowser::new(&g).eigenvector_centrality();
+        assert!(ranking.converged());
+        assert_close(1.0 / 2.0f64.sqrt(), ranking.score(0));
+        assert_close(1.0 / 6.0f64.sqrt(), ranking.score(1));
+    }
+
+    #[test]
+    fn test_hits() {
+        let g: DirectedSimpleGraphImpl = build(4, &[(0, 2), (1, 2), (1, 3)]);
+        let x = LinkAnalysisBrowser::new(&g).hits();
+        assert!(x.hubs().converged());
+        assert_close(1.0, x.hubs().scores().iter().sum());
+        assert_close(1.0, x.authorities().scores().iter().sum());
+        assert!(x.hubs().score(1) > x.hubs().score(0));
+        assert!(x.authorities().score(2) > x.authorities().score(3));
+        assert_close(0.0, x.authorities().score(0));
+    }
+}
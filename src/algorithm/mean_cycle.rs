@@ -0,0 +1,221 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use graph::DecoratedGraph;
+use graph::DirectedGraph;
+
+/// A cycle with its total weight and its length (number of edges).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeanCycle {
+    vertices: Vec<usize>,
+    edges: Vec<usize>,
+    weight: usize,
+}
+
+impl MeanCycle {
+    /// The vertices of the cycle: `edges()[i]` goes from `vertices()[i]` to the next vertex
+    pub fn vertices(&self) -> &[usize] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> &[usize] {
+        &self.edges
+    }
+
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// weight / len
+    pub fn mean(&self) -> f64 {
+        self.weight as f64 / self.len() as f64
+    }
+}
+
+/// Karp's minimum mean cycle, in O(V * E) time and O(V^2) space. The weight of an edge is its
+/// value (0 if there is no value).
+///
+/// d[k][v] is the minimum weight of a walk of exactly k edges ending at v (starting anywhere).
+/// The minimum mean is the minimum over v of the maximum over k < n of
+/// (d[n][v] - d[k][v]) / (n - k), and the walk of n edges ending at the best v contains a
+/// cycle of minimum mean.
+pub struct MinimumMeanCycleBrowser<'a, G, V>
+    where G: 'a + DirectedGraph<'a> + DecoratedGraph<'a, V, &'a usize>,
+          V: 'a + PartialEq + Clone + Debug
+{
+    decorated_graph: &'a G,
+    phantom_v: PhantomData<V>,
+}
+
+impl<'a, G, V> MinimumMeanCycleBrowser<'a, G, V>
+    where G: 'a + DirectedGraph<'a> + DecoratedGraph<'a, V, &'a usize>,
+          V: 'a + PartialEq + Clone + Debug
+{
+    pub fn new(decorated_graph: &'a G) -> MinimumMeanCycleBrowser<'a, G, V> {
+        MinimumMeanCycleBrowser {
+            decorated_graph,
+            phantom_v: PhantomData,
+        }
+    }
+
+    /// A cycle of minimum mean weight, or None if the graph is acyclic.
+    pub fn browse(&self) -> Option<MeanCycle> {
+        let g = self.decorated_graph;
+        let n = g.vertices_size();
+        let m = g.vertices_max();
+        let mut edges = Vec::new();
+        for u in g.vertices_iter() {
+            for v in g.adjacent_vertices_iter(u) {
+                for (e, o_weight) in g.edges_values_iter(u, v) {
+                    edges.push((u, v, e, *o_weight.unwrap_or(&0)));
+                }
+            }
+        }
+
+        let mut dist: Vec<Vec<Option<usize>>> = vec![vec![None; m]; n + 1];
+        // (previous vertex, edge, weight)
+        let mut previous: Vec<Vec<Option<(usize, usize, usize)>>> = vec![vec![None; m]; n + 1];
+        for u in g.vertices_iter() {
+            dist[0][u] = Some(0);
+        }
+        for k in 1..=n {
+            for &(u, v, e, w) in &edges {
+                if let Some(d) = dist[k - 1][u] {
+                    if dist[k][v].map_or(true, |d2| d + w < d2) {
+                        dist[k][v] = Some(d + w);
+                        previous[k][v] = Some((u, e, w));
+                    }
+                }
+            }
+        }
+
+        // the mean is a fraction (weight, len), compared by cross multiplication
+        let less = |(a, b): (i128, i128), (c, d): (i128, i128)| (a * d).cmp(&(c * b)) == Ordering::Less;
+        let mut best: Option<((i128, i128), usize)> = None;
+        for v in g.vertices_iter() {
+            let dn = match dist[n][v] {
+                Some(d) => d as i128,
+                None => continue,
+            };
+            let mut worst: Option<(i128, i128)> = None;
+            for (k, o_dk) in dist.iter().take(n).map(|level| level[v]).enumerate() {
+                if let Some(dk) = o_dk {
+                    let mean = (dn - dk as i128, (n - k) as i128);
+                    if worst.map_or(true, |w| less(w, mean)) {
+                        worst = Some(mean);
+                    }
+                }
+            }
+            if let Some(w) = worst {
+                if best.map_or(true, |(b, _)| less(w, b)) {
+                    best = Some((w, v));
+                }
+            }
+        }
+
+        let (_, v) = best?;
+        // walk back from (n, v) until a vertex is repeated: the steps in between are a cycle
+        let mut seen_at: Vec<Option<usize>> = vec![None; m];
+        let mut steps: Vec<Option<(usize, usize, usize)>> = vec![None; n + 1];
+        let mut x = v;
+        for k in (0..=n).rev() {
+            if let Some(k2) = seen_at[x] {
+                let mut cycle = MeanCycle {
+                    vertices: vec![x],
+                    edges: Vec::new(),
+                    weight: 0,
+                };
+                for &(y, e, w) in steps[k + 1..=k2].iter().flatten() {
+                    cycle.vertices.push(y);
+                    cycle.edges.push(e);
+                    cycle.weight += w;
+                }
+                cycle.vertices.pop();
+                return Some(cycle);
+            }
+            seen_at[x] = Some(k);
+            if k > 0 {
+                let (u, e, w) = previous[k][x].expect("a walk of k edges");
+                steps[k] = Some((x, e, w));
+                x = u;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::Graph;
+    use graph::GraphBuilder;
+    use graph::GraphDecorator;
+    use graph::examples::decorated_graph1;
+
+    use super::*;
+
+    #[test]
+    fn test_acyclic() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let dg = decorated_graph1(&mut g);
+        assert_eq!(None, MinimumMeanCycleBrowser::new(&dg).browse());
+    }
+
+    #[test]
+    fn test_minimum_mean_cycle() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut dg = GraphDecorator::new(&mut g);
+        for c in "ABCDE".chars() {
+            dg.add_vertex(c);
+        }
+        // A-B-C-A: 12 / 3, C-D-C: 10 / 2, D-E-D: 11 / 2
+        dg.add_edge(0, 1, 4);
+        dg.add_edge(1, 2, 4);
+        dg.add_edge(2, 0, 4);
+        dg.add_edge(2, 3, 5);
+        dg.add_edge(3, 2, 5);
+        dg.add_edge(3, 4, 1);
+        dg.add_edge(4, 3, 10);
+        let cycle = MinimumMeanCycleBrowser::new(&dg).browse().expect("a cycle");
+        assert_eq!(12, cycle.weight());
+        assert_eq!(3, cycle.len());
+        assert_eq!(4.0, cycle.mean());
+        let mut vertices = cycle.vertices().to_vec();
+        vertices.sort();
+        assert_eq!(vec![0, 1, 2], vertices);
+        for (i, &e) in cycle.edges().iter().enumerate() {
+            let (u, v) = dg.get_vertices_from_edge(e).expect("an edge");
+            assert_eq!(cycle.vertices()[i], u);
+            assert_eq!(cycle.vertices()[(i + 1) % cycle.len()], v);
+        }
+    }
+}
@@ -24,5 +24,20 @@ pub mod bfs;
 pub mod dijkstra;
 pub mod bellman_ford;
 pub mod floyd_warshall;
-mod single_source_shortest_paths;
-mod all_pairs_shortest_paths;
+pub mod biconnected_components;
+pub mod eulerian_path;
+pub mod coloring;
+pub mod cliques;
+pub mod vf2;
+pub mod centrality;
+pub mod link_analysis;
+pub mod community;
+pub mod triangles;
+pub mod cores;
+pub mod eccentricity;
+pub mod dominators;
+pub mod transitive_closure;
+pub mod cycles;
+pub mod mean_cycle;
+pub mod single_source_shortest_paths;
+pub mod all_pairs_shortest_paths;
@@ -0,0 +1,280 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::basic_graph::BasicGraph;
+use graph::DirectedGraph;
+use graph::GraphBuilder;
+use util::BitSet;
+
+/// Reachability in a directed graph, computed once in O(V * (V + E) / 64).
+///
+/// The strongly connected components are found with Tarjan's algorithm: they are numbered in
+/// reverse topological order, hence the set of vertices reachable from a component is the union
+/// of its vertices and of the sets of the components it points to, that are already known.
+/// A query `reachable(u, v)` is then a lookup in a bitset.
+pub struct TransitiveClosureBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    g: &'a G,
+    component: Vec<usize>,
+    components_count: usize,
+    has_self_loop: bool,
+    // component => reachable vertices
+    reach: Vec<BitSet>,
+}
+
+impl<'a, G> TransitiveClosureBrowser<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> TransitiveClosureBrowser<'a, G> {
+        let mut b = TransitiveClosureBrowser {
+            g,
+            component: vec![0; g.vertices_max()],
+            components_count: 0,
+            has_self_loop: false,
+            reach: Vec::new(),
+        };
+        b.browse();
+        b
+    }
+
+    fn browse(&mut self) {
+        self.strongly_connected_components();
+        let g = self.g;
+        let n = g.vertices_max();
+        let mut members = vec![Vec::new(); self.components_count];
+        for u in g.vertices_iter() {
+            members[self.component[u]].push(u);
+        }
+        self.reach = vec![BitSet::new(n); self.components_count];
+        for (c, vertices) in members.iter().enumerate() {
+            let (done, rest) = self.reach.split_at_mut(c);
+            let reach = &mut rest[0];
+            for &u in vertices {
+                reach.insert(u);
+                for v in g.adjacent_vertices_iter(u) {
+                    if v == u {
+                        self.has_self_loop = true;
+                    }
+                    let d = self.component[v];
+                    if d != c {
+                        reach.union_with(&done[d]);
+                    }
+                }
+            }
+        }
+    }
+
+    // Tarjan, without recursion
+    fn strongly_connected_components(&mut self) {
+        let g = self.g;
+        let n = g.vertices_max();
+        let mut index: Vec<Option<usize>> = vec![None; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut count = 0;
+        for root in g.vertices_iter() {
+            if index[root].is_some() {
+                continue;
+            }
+            index[root] = Some(count);
+            low[root] = count;
+            count += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            let mut call_stack = vec![(root, g.adjacent_vertices_iter(root))];
+            while let Some(&mut (u, ref mut it)) = call_stack.last_mut() {
+                if let Some(v) = it.next() {
+                    match index[v] {
+                        None => {
+                            index[v] = Some(count);
+                            low[v] = count;
+                            count += 1;
+                            stack.push(v);
+                            on_stack[v] = true;
+                            call_stack.push((v, g.adjacent_vertices_iter(v)));
+                        }
+                        Some(i) if on_stack[v] && i < low[u] => low[u] = i,
+                        _ => {}
+                    }
+                    continue;
+                }
+                call_stack.pop();
+                if let Some(&(p, _)) = call_stack.last() {
+                    if low[u] < low[p] {
+                        low[p] = low[u];
+                    }
+                }
+                if Some(low[u]) == index[u] {
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        self.component[w] = self.components_count;
+                        if w == u {
+                            break;
+                        }
+                    }
+                    self.components_count += 1;
+                }
+            }
+        }
+    }
+
+    /// true if there is a path from u to v. A vertex is always reachable from itself.
+    pub fn reachable(&self, u: usize, v: usize) -> bool {
+        self.reach[self.component[u]].contains(v)
+    }
+
+    /// The vertices reachable from u (u included)
+    pub fn reachable_vertices(&self, u: usize) -> &BitSet {
+        &self.reach[self.component[u]]
+    }
+
+    /// The strongly connected component of u. The components are numbered in reverse
+    /// topological order: if there is an edge from c1 to c2 != c1, then c2 < c1.
+    pub fn component(&self, u: usize) -> usize {
+        self.component[u]
+    }
+
+    pub fn components_count(&self) -> usize {
+        self.components_count
+    }
+
+    /// true if the graph has no cycle (and no self loop)
+    pub fn is_acyclic(&self) -> bool {
+        !self.has_self_loop && self.components_count == self.g.vertices_size()
+    }
+
+    /// The transitive closure: an edge u->v for every u != v such that v is reachable from u.
+    /// The vertices keep their ids.
+    pub fn closure<'b, T>(&self) -> T
+        where T: GraphBuilder<'b>
+    {
+        let mut closure: T = self.same_vertices();
+        for u in self.g.vertices_iter() {
+            for v in self.reachable_vertices(u).iter() {
+                if v != u {
+                    closure.add_edge(u, v);
+                }
+            }
+        }
+        closure
+    }
+
+    /// The transitive reduction of a DAG: the smallest graph with the same reachability. An edge
+    /// u->v is kept if v is not reachable from any other successor of u. The vertices keep their
+    /// ids. Return None if the graph is not acyclic (the reduction is not unique).
+    pub fn reduction<'b, T>(&self) -> Option<T>
+        where T: GraphBuilder<'b>
+    {
+        if !self.is_acyclic() {
+            return None;
+        }
+        let g = self.g;
+        let mut reduction: T = self.same_vertices();
+        for u in g.vertices_iter() {
+            let mut successors: Vec<usize> = g.adjacent_vertices_iter(u).collect();
+            successors.sort();
+            successors.dedup();
+            for &v in &successors {
+                if !successors.iter().any(|&w| w != v && self.reachable(w, v)) {
+                    reduction.add_edge(u, v);
+                }
+            }
+        }
+        Some(reduction)
+    }
+
+    fn same_vertices<'b, T>(&self) -> T
+        where T: GraphBuilder<'b>
+    {
+        let mut h: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..self.g.vertices_max() {
+            h.create_vertex();
+        }
+        let mut present = vec![false; self.g.vertices_max()];
+        for u in self.g.vertices_iter() {
+            present[u] = true;
+        }
+        for u in (0..self.g.vertices_max()).rev() {
+            if !present[u] {
+                h.remove_vertex(u);
+            }
+        }
+        h
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::DirectedSimpleGraphImpl;
+    use graph::Graph;
+    use graph::examples::graph1;
+
+    use super::*;
+
+    #[test]
+    fn test_reachable() {
+        let g = graph1::<DirectedSimpleGraphImpl>();
+        let b = TransitiveClosureBrowser::new(&g);
+        assert!(b.reachable(0, 4));
+        assert!(b.reachable(4, 6));
+        assert!(b.reachable(2, 3));
+        assert!(!b.reachable(1, 0));
+        assert!(!b.reachable(0, 7));
+        assert!(b.reachable(7, 7));
+        assert_eq!(b.component(3), b.component(5));
+        assert!(!b.is_acyclic());
+        assert_eq!(vec![3, 4, 5, 6], b.reachable_vertices(3).iter().collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_closure_and_reduction() {
+        let mut g: DirectedSimpleGraphImpl = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..5 {
+            g.create_vertex();
+        }
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(0, 2);
+        g.add_edge(2, 3);
+        g.add_edge(0, 3);
+        g.add_edge(4, 3);
+        let b = TransitiveClosureBrowser::new(&g);
+        assert!(b.is_acyclic());
+        assert_eq!(5, b.components_count());
+
+        let closure: DirectedSimpleGraphImpl = b.closure();
+        assert_eq!(7, closure.edges_size());
+        assert!(closure.get_edges_from_vertices_iter(1, 3).next().is_some());
+
+        let reduction: DirectedSimpleGraphImpl = b.reduction().expect("a DAG");
+        assert_eq!(4, reduction.edges_size());
+        assert!(reduction.get_edges_from_vertices_iter(0, 2).next().is_none());
+        assert!(reduction.get_edges_from_vertices_iter(0, 3).next().is_none());
+        assert!(reduction.get_edges_from_vertices_iter(4, 3).next().is_some());
+    }
+
+    #[test]
+    fn test_no_reduction_with_cycle() {
+        let g = graph1::<DirectedSimpleGraphImpl>();
+        let b = TransitiveClosureBrowser::new(&g);
+        assert!(b.reduction::<DirectedSimpleGraphImpl>().is_none());
+    }
+}
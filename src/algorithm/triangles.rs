@@ -0,0 +1,164 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::UndirectedGraph;
+
+/// Triangles and clustering coefficients of an undirected graph. Self loops are ignored.
+///
+/// The triangles are counted by the forward algorithm: the vertices are ranked by degree, every
+/// edge is oriented from the lower rank to the higher rank, and a triangle is found once, from
+/// its vertex of lowest rank, by intersecting the out-neighbors of the two first vertices. A
+/// vertex has at most O(sqrt(m)) out-neighbors of higher rank, hence a O(m^1.5) complexity.
+pub struct TrianglesBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    g: &'a G,
+    degree: Vec<usize>,
+    vertex_triangles: Vec<usize>,
+    triangles: usize,
+}
+
+impl<'a, G> TrianglesBrowser<'a, G>
+    where G: 'a + UndirectedGraph<'a>
+{
+    pub fn new(g: &'a G) -> TrianglesBrowser<'a, G> {
+        let mut b = TrianglesBrowser {
+            g,
+            degree: vec![0; g.vertices_max()],
+            vertex_triangles: vec![0; g.vertices_max()],
+            triangles: 0,
+        };
+        b.browse();
+        b
+    }
+
+    fn browse(&mut self) {
+        let g = self.g;
+        for u in g.vertices_iter() {
+            self.degree[u] = g.adjacent_vertices_iter(u).filter(|&v| v != u).count();
+        }
+        let degree = &self.degree;
+        let rank = |u: usize| (degree[u], u);
+        let mut out = vec![Vec::new(); g.vertices_max()];
+        for u in g.vertices_iter() {
+            out[u] = g.adjacent_vertices_iter(u).filter(|&v| rank(v) > rank(u)).collect();
+        }
+        let mut marked = vec![false; g.vertices_max()];
+        for u in g.vertices_iter() {
+            for &v in &out[u] {
+                marked[v] = true;
+            }
+            for &v in &out[u] {
+                for &w in &out[v] {
+                    if marked[w] {
+                        self.triangles += 1;
+                        self.vertex_triangles[u] += 1;
+                        self.vertex_triangles[v] += 1;
+                        self.vertex_triangles[w] += 1;
+                    }
+                }
+            }
+            for &v in &out[u] {
+                marked[v] = false;
+            }
+        }
+    }
+
+    /// return the number of triangles of the graph
+    pub fn triangles(&self) -> usize {
+        self.triangles
+    }
+
+    /// return the number of triangles that contain u
+    pub fn vertex_triangles(&self, u: usize) -> usize {
+        self.vertex_triangles[u]
+    }
+
+    /// return the fraction of pairs of neighbors of u that are adjacent
+    pub fn local_clustering(&self, u: usize) -> f64 {
+        let d = self.degree[u];
+        if d < 2 {
+            0.0
+        } else {
+            2.0 * self.vertex_triangles[u] as f64 / (d * (d - 1)) as f64
+        }
+    }
+
+    /// return the mean of the local clustering coefficients of all vertices
+    pub fn average_clustering(&self) -> f64 {
+        let n = self.g.vertices_size();
+        if n == 0 {
+            return 0.0;
+        }
+        self.g.vertices_iter().map(|u| self.local_clustering(u)).sum::<f64>() / n as f64
+    }
+
+    /// return the fraction of connected triples of vertices that are closed: three times the
+    /// number of triangles, divided by the number of paths of length 2
+    pub fn transitivity(&self) -> f64 {
+        let triples: usize = self.g.vertices_iter().map(|u| self.degree[u])
+            .filter(|&d| d >= 2).map(|d| d * (d - 1) / 2).sum();
+        if triples == 0 {
+            0.0
+        } else {
+            3.0 * self.triangles as f64 / triples as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::examples::graph1;
+    use graph::GraphBuilder;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    #[test]
+    fn test_graph1() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let b = TrianglesBrowser::new(&g);
+        assert_eq!(2, b.triangles());
+        assert_eq!(1, b.vertex_triangles(4));
+        assert_eq!(0, b.vertex_triangles(0));
+        assert!((b.local_clustering(4) - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(1.0, b.local_clustering(11));
+        assert_eq!(0.0, b.local_clustering(1));
+        assert!((b.transitivity() - 6.0 / 19.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_complete() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..5 {
+            g.create_vertex();
+        }
+        for u in 0..5 {
+            for v in u + 1..5 {
+                g.add_edge(u, v);
+            }
+        }
+        let b = TrianglesBrowser::new(&g);
+        assert_eq!(10, b.triangles());
+        assert_eq!(6, b.vertex_triangles(0));
+        assert_eq!(1.0, b.average_clustering());
+        assert_eq!(1.0, b.transitivity());
+    }
+}
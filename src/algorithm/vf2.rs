@@ -0,0 +1,451 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use graph::DecoratedGraph;
+use graph::Graph;
+
+/// What a mapping from the pattern vertices to the target vertices must preserve
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MatchKind {
+    /// a bijection that preserves edges and non-edges
+    Isomorphism,
+    /// an injection that preserves edges and non-edges between the mapped vertices
+    InducedSubgraph,
+    /// an injection that preserves edges (monomorphism)
+    Subgraph,
+}
+
+/// The successors and predecessors of every vertex. For an undirected graph, both are equal.
+struct Neighbors {
+    vertices: Vec<usize>,
+    successors: Vec<HashSet<usize>>,
+    predecessors: Vec<HashSet<usize>>,
+    edges_count: usize,
+}
+
+impl Neighbors {
+    fn new<'g, G>(g: &'g G) -> Neighbors
+        where G: Graph<'g>
+    {
+        let mut successors = vec![HashSet::new(); g.vertices_max()];
+        let mut predecessors = vec![HashSet::new(); g.vertices_max()];
+        let mut edges_count = 0;
+        for u in g.vertices_iter() {
+            for v in g.adjacent_vertices_iter(u) {
+                successors[u].insert(v);
+                predecessors[v].insert(u);
+                edges_count += 1;
+            }
+        }
+        Neighbors {
+            vertices: g.vertices_iter().collect(),
+            successors,
+            predecessors,
+            edges_count,
+        }
+    }
+}
+
+type VertexMatch<'a> = Box<dyn Fn(usize, usize) -> bool + 'a>;
+type EdgeMatch<'a> = Box<dyn Fn((usize, usize), (usize, usize)) -> bool + 'a>;
+
+/// A VF2-style matcher: the pattern vertices are mapped one after the other, in a
+/// connectivity-preserving order, and a partial mapping is extended only if it is consistent
+/// with the edges between the vertices already mapped.
+pub struct VF2Browser<'a> {
+    pattern: Neighbors,
+    target: Neighbors,
+    kind: MatchKind,
+    vertex_match: Option<VertexMatch<'a>>,
+    edge_match: Option<EdgeMatch<'a>>,
+}
+
+impl<'a> VF2Browser<'a> {
+    pub fn new<'g1, 'g2, G1, G2>(pattern: &'g1 G1, target: &'g2 G2, kind: MatchKind) -> VF2Browser<'a>
+        where G1: Graph<'g1>,
+              G2: Graph<'g2>
+    {
+        VF2Browser {
+            pattern: Neighbors::new(pattern),
+            target: Neighbors::new(target),
+            kind,
+            vertex_match: None,
+            edge_match: None,
+        }
+    }
+
+    /// A pattern vertex u may be mapped to a target vertex v only if `f(u, v)`
+    pub fn with_vertex_match<F>(mut self, f: F) -> VF2Browser<'a>
+        where F: Fn(usize, usize) -> bool + 'a
+    {
+        self.vertex_match = Some(Box::new(f));
+        self
+    }
+
+    /// A pattern edge (u1, u2) may be mapped to a target edge (v1, v2) only if
+    /// `f((u1, u2), (v1, v2))`
+    pub fn with_edge_match<F>(mut self, f: F) -> VF2Browser<'a>
+        where F: Fn((usize, usize), (usize, usize)) -> bool + 'a
+    {
+        self.edge_match = Some(Box::new(f));
+        self
+    }
+
+    /// Match the vertices and the edges on their values (see `DecoratedGraph`)
+    pub fn with_decorations<G1, G2, V, E>(self, pattern: &'a G1, target: &'a G2) -> VF2Browser<'a>
+        where G1: DecoratedGraph<'a, V, E>,
+              G2: DecoratedGraph<'a, V, E>,
+              V: 'a + PartialEq + Clone + Debug,
+              E: 'a + PartialEq + Clone + Debug
+    {
+        let pattern_values = Self::vertices_values(pattern);
+        let target_values = Self::vertices_values(target);
+        let pattern_edges_values = Self::edges_values(pattern);
+        let target_edges_values = Self::edges_values(target);
+        self.with_vertex_match(move |u, v| pattern_values[u] == target_values[v])
+            .with_edge_match(move |e1, e2| pattern_edges_values.get(&e1) == target_edges_values.get(&e2))
+    }
+
+    fn vertices_values<G, V, E>(g: &'a G) -> Vec<Option<V>>
+        where G: DecoratedGraph<'a, V, E>,
+              V: 'a + PartialEq + Clone + Debug,
+              E: 'a + PartialEq + Clone + Debug
+    {
+        let mut values = vec![None; g.vertices_max()];
+        for (u, value) in g.vertices_values_iter() {
+            values[u] = value;
+        }
+        values
+    }
+
+    fn edges_values<G, V, E>(g: &'a G) -> HashMap<(usize, usize), Option<E>>
+        where G: DecoratedGraph<'a, V, E>,
+              V: 'a + PartialEq + Clone + Debug,
+              E: 'a + PartialEq + Clone + Debug
+    {
+        let mut values = HashMap::new();
+        for u in g.vertices_iter() {
+            for v in g.adjacent_vertices_iter(u) {
+                if let Some((_, value)) = g.edges_values_iter(u, v).next() {
+                    values.insert((u, v), value);
+                }
+            }
+        }
+        values
+    }
+
+    /// return true if there is at least one mapping
+    pub fn is_match(&self) -> bool {
+        self.mappings_iter().next().is_some()
+    }
+
+    /// return a lazy iterator on the mappings pattern vertex -> target vertex
+    pub fn mappings_iter(&self) -> MappingsIter<'_, 'a> {
+        let possible = match self.kind {
+            MatchKind::Isomorphism => self.pattern.vertices.len() == self.target.vertices.len() &&
+                self.pattern.edges_count == self.target.edges_count,
+            _ => self.pattern.vertices.len() <= self.target.vertices.len() &&
+                self.pattern.edges_count <= self.target.edges_count,
+        };
+        MappingsIter {
+            browser: self,
+            order: self.pattern_order(),
+            pattern_to_target: vec![None; self.pattern.successors.len()],
+            target_to_pattern: vec![None; self.target.successors.len()],
+            stack: Vec::new(),
+            state: if possible { IterState::Start } else { IterState::Done },
+        }
+    }
+
+    /// A BFS order, starting from the vertex of maximum degree in every component, so that
+    /// every vertex but the first of a component has a mapped neighbor
+    fn pattern_order(&self) -> Vec<usize> {
+        let p = &self.pattern;
+        let degree = |u: usize| p.successors[u].len() + p.predecessors[u].len();
+        let mut vertices = p.vertices.clone();
+        vertices.sort_by_key(|&u| (usize::MAX - degree(u), u));
+        let mut seen = vec![false; p.successors.len()];
+        let mut order = Vec::with_capacity(vertices.len());
+        for root in vertices {
+            if seen[root] {
+                continue;
+            }
+            seen[root] = true;
+            let mut queue = VecDeque::new();
+            queue.push_back(root);
+            while let Some(u) = queue.pop_front() {
+                order.push(u);
+                for &v in p.successors[u].iter().chain(p.predecessors[u].iter()) {
+                    if !seen[v] {
+                        seen[v] = true;
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+        order
+    }
+}
+
+enum IterState {
+    Start,
+    Running,
+    Done,
+}
+
+/// An iterator on mappings. The recursion is replaced by a stack of candidates, hence the caller
+/// may stop at any time.
+pub struct MappingsIter<'b, 'a: 'b> {
+    browser: &'b VF2Browser<'a>,
+    order: Vec<usize>,
+    pattern_to_target: Vec<Option<usize>>,
+    target_to_pattern: Vec<Option<usize>>,
+    stack: Vec<Vec<usize>>,
+    state: IterState,
+}
+
+impl<'b, 'a: 'b> MappingsIter<'b, 'a> {
+    /// The target vertices that may be mapped to u: the images of the neighbors of u restrict
+    /// the candidates
+    fn candidates(&self, u: usize) -> Vec<usize> {
+        let p = &self.browser.pattern;
+        let t = &self.browser.target;
+        let from_predecessor = p.predecessors[u].iter().filter_map(|&w| self.pattern_to_target[w])
+            .map(|tw| &t.successors[tw]).next();
+        let from_successor = p.successors[u].iter().filter_map(|&w| self.pattern_to_target[w])
+            .map(|tw| &t.predecessors[tw]).next();
+        let candidates: Vec<usize> = match from_predecessor.or(from_successor) {
+            Some(set) => set.iter().cloned().collect(),
+            None => t.vertices.clone(),
+        };
+        let mut candidates: Vec<usize> = candidates.into_iter()
+            .filter(|&v| self.target_to_pattern[v].is_none()).collect();
+        candidates.sort_by(|a, b| b.cmp(a)); // candidates are popped
+        candidates
+    }
+
+    fn feasible(&self, u: usize, v: usize) -> bool {
+        let browser = self.browser;
+        let (p, t) = (&browser.pattern, &browser.target);
+        let exact = browser.kind != MatchKind::Subgraph;
+        let degrees_ok = if browser.kind == MatchKind::Isomorphism {
+            p.successors[u].len() == t.successors[v].len() && p.predecessors[u].len() == t.predecessors[v].len()
+        } else {
+            p.successors[u].len() <= t.successors[v].len() && p.predecessors[u].len() <= t.predecessors[v].len()
+        };
+        if !degrees_ok {
+            return false;
+        }
+        if let Some(ref f) = browser.vertex_match {
+            if !f(u, v) {
+                return false;
+            }
+        }
+        // u is tentatively mapped to v
+        let image = |w: usize| if w == u { Some(v) } else { self.pattern_to_target[w] };
+        let antecedent = |w: usize| if w == v { Some(u) } else { self.target_to_pattern[w] };
+        for &w in &p.successors[u] {
+            if let Some(tw) = image(w) {
+                if !t.successors[v].contains(&tw) || !self.edge_match((u, w), (v, tw)) {
+                    return false;
+                }
+            }
+        }
+        for &w in &p.predecessors[u] {
+            if let Some(tw) = image(w) {
+                if !t.predecessors[v].contains(&tw) || !self.edge_match((w, u), (tw, v)) {
+                    return false;
+                }
+            }
+        }
+        if exact {
+            let missing_successor = t.successors[v].iter().filter_map(|&tw| antecedent(tw))
+                .any(|w| !p.successors[u].contains(&w));
+            let missing_predecessor = t.predecessors[v].iter().filter_map(|&tw| antecedent(tw))
+                .any(|w| !p.predecessors[u].contains(&w));
+            if missing_successor || missing_predecessor {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn edge_match(&self, e1: (usize, usize), e2: (usize, usize)) -> bool {
+        match self.browser.edge_match {
+            None => true,
+            Some(ref f) => f(e1, e2),
+        }
+    }
+
+    fn mapping(&self) -> HashMap<usize, usize> {
+        self.order.iter().map(|&u| (u, self.pattern_to_target[u].expect("u is mapped"))).collect()
+    }
+}
+
+impl<'b, 'a: 'b> Iterator for MappingsIter<'b, 'a> {
+    type Item = HashMap<usize, usize>;
+
+    fn next(&mut self) -> Option<HashMap<usize, usize>> {
+        match self.state {
+            IterState::Done => { return None; }
+            IterState::Start => {
+                self.state = IterState::Running;
+                if self.order.is_empty() {
+                    self.state = IterState::Done;
+                    return Some(HashMap::new());
+                }
+                let candidates = self.candidates(self.order[0]);
+                self.stack.push(candidates);
+            }
+            IterState::Running => {}
+        }
+        while let Some(depth) = self.stack.len().checked_sub(1) {
+            let u = self.order[depth];
+            // undo the previous mapping of u
+            if let Some(v) = self.pattern_to_target[u].take() {
+                self.target_to_pattern[v] = None;
+            }
+            match self.stack[depth].pop() {
+                None => { self.stack.pop(); }
+                Some(v) => {
+                    if self.feasible(u, v) {
+                        self.pattern_to_target[u] = Some(v);
+                        self.target_to_pattern[v] = Some(u);
+                        if depth + 1 == self.order.len() {
+                            return Some(self.mapping());
+                        }
+                        let candidates = self.candidates(self.order[depth + 1]);
+                        self.stack.push(candidates);
+                    }
+                }
+            }
+        }
+        self.state = IterState::Done;
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::examples::decorated_graph1;
+    use graph::examples::graph1;
+    use graph::GraphBuilder;
+    use graph::GraphDecorator;
+    use graph::UndirectedSimpleGraphImpl;
+
+    use super::*;
+
+    fn build<'a, T>(n: usize, edges: &[(usize, usize)]) -> T
+        where T: Graph<'a> + GraphBuilder<'a>
+    {
+        let mut g: T = GraphBuilder::new(BasicGraph::new());
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        for &(u, v) in edges {
+            g.add_edge(u, v);
+        }
+        g
+    }
+
+    #[test]
+    fn test_isomorphism() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        // the same graph, with the vertices in reverse order
+        let mut edges = Vec::new();
+        for e in g.edges_iter() {
+            let (u, v) = g.get_vertices_from_edge(e).expect("an edge");
+            edges.push((12 - u, 12 - v));
+        }
+        let h: UndirectedSimpleGraphImpl = build(13, &edges);
+        let b = VF2Browser::new(&g, &h, MatchKind::Isomorphism);
+        let mapping = b.mappings_iter().next().expect("a mapping");
+        for e in g.edges_iter() {
+            let (u, v) = g.get_vertices_from_edge(e).expect("an edge");
+            assert!(h.get_edges_from_vertices_iter(mapping[&u], mapping[&v]).next().is_some());
+        }
+
+        let h: UndirectedSimpleGraphImpl = build(13, &edges[1..]);
+        assert!(!VF2Browser::new(&g, &h, MatchKind::Isomorphism).is_match());
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let g = graph1::<UndirectedSimpleGraphImpl>();
+        let triangle: UndirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2), (2, 0)]);
+        let b = VF2Browser::new(&triangle, &g, MatchKind::InducedSubgraph);
+        // two triangles, 6 automorphisms
+        assert_eq!(12, b.mappings_iter().count());
+
+        let path: UndirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2)]);
+        let induced = VF2Browser::new(&path, &g, MatchKind::InducedSubgraph).mappings_iter().count();
+        let not_induced = VF2Browser::new(&path, &g, MatchKind::Subgraph).mappings_iter().count();
+        assert_eq!(not_induced, induced + 12);
+    }
+
+    #[test]
+    fn test_directed() {
+        let cycle: DirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2), (2, 0)]);
+        let g: DirectedSimpleGraphImpl = build(4, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 1)]);
+        let b = VF2Browser::new(&cycle, &g, MatchKind::Subgraph);
+        assert_eq!(6, b.mappings_iter().count());
+        let g: DirectedSimpleGraphImpl = build(3, &[(0, 1), (1, 2), (0, 2)]);
+        assert!(!VF2Browser::new(&cycle, &g, MatchKind::Subgraph).is_match());
+    }
+
+    #[test]
+    fn test_decorations() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let dg = decorated_graph1(&mut g);
+        let mut p = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut pattern = GraphDecorator::new(&mut p);
+        let u = pattern.add_vertex('X');
+        let v = pattern.add_vertex('Y');
+        pattern.add_edge(u, v, 1);
+        // 13 edges, 6 of weight 1
+        let b = VF2Browser::new(&pattern, &dg, MatchKind::Subgraph);
+        assert_eq!(13, b.mappings_iter().count());
+        let b = VF2Browser::new(&pattern, &dg, MatchKind::Subgraph)
+            .with_edge_match(|(u1, v1), (u2, v2)| {
+                pattern.edges_values_iter(u1, v1).next().map(|(_, w)| w) ==
+                    dg.edges_values_iter(u2, v2).next().map(|(_, w)| w)
+            });
+        assert_eq!(6, b.mappings_iter().count());
+        let b = VF2Browser::new(&pattern, &dg, MatchKind::Subgraph).with_decorations(&pattern, &dg);
+        assert!(!b.is_match());
+
+        let mut p = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut pattern = GraphDecorator::new(&mut p);
+        let u = pattern.add_vertex('L');
+        let v = pattern.add_vertex('J');
+        pattern.add_edge(u, v, 1);
+        let b = VF2Browser::new(&pattern, &dg, MatchKind::Subgraph).with_decorations(&pattern, &dg);
+        let mappings: Vec<HashMap<usize, usize>> = b.mappings_iter().collect();
+        assert_eq!(1, mappings.len());
+        assert_eq!(11, mappings[0][&u]);
+        assert_eq!(9, mappings[0][&v]);
+    }
+}
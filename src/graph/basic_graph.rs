@@ -18,10 +18,105 @@
 /// along with this program.  If not, see <http://www.gnu.org/licenses/>.
 /// ***************************************************************************
 use std::collections::HashMap;
-use std::collections::hash_map;
 use util::dense_vec_indices::DenseVecIndices;
 use util::dense_vec_indices::UsedIndicesIter;
 use util::edge_set::EdgeSet;
+use graph::GraphError;
+
+/// A vertex index tagged with the generation of its slot. The handle becomes stale once the
+/// vertex is removed, even if the index is given to a new vertex later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VertexHandle {
+    index: usize,
+    generation: u32,
+}
+
+impl VertexHandle {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// An edge index tagged with the generation of its slot. See `VertexHandle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EdgeHandle {
+    index: usize,
+    generation: u32,
+}
+
+impl EdgeHandle {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// The old -> new ids of the vertices and edges of a compacted graph. The ids are renumbered
+/// from 0, in increasing order; a removed vertex or edge has no new id.
+pub struct Compaction {
+    vertices: Vec<Option<usize>>,
+    edges: Vec<Option<usize>>,
+    vertices_size: usize,
+    edges_size: usize,
+}
+
+impl Compaction {
+    /// Number the used vertices, then the used edges whose vertices are still used.
+    pub fn new<F>(vertices: &DenseVecIndices, edges: &DenseVecIndices, vertices_of: F) -> Compaction
+        where F: Fn(usize) -> Option<(usize, usize)>
+    {
+        let mut compaction = Compaction {
+            vertices: vec![None; vertices.max()],
+            edges: vec![None; edges.max()],
+            vertices_size: 0,
+            edges_size: 0,
+        };
+        for u in vertices.used_indices_iter() {
+            compaction.vertices[u] = Some(compaction.vertices_size);
+            compaction.vertices_size += 1;
+        }
+        for e in edges.used_indices_iter() {
+            if compaction.arc(vertices_of(e)).is_some() {
+                compaction.edges[e] = Some(compaction.edges_size);
+                compaction.edges_size += 1;
+            }
+        }
+        compaction
+    }
+
+    /// The new id of the vertex u
+    pub fn vertex(&self, u: usize) -> Option<usize> {
+        self.vertices.get(u).cloned().unwrap_or(None)
+    }
+
+    /// The new id of the edge e
+    pub fn edge(&self, e: usize) -> Option<usize> {
+        self.edges.get(e).cloned().unwrap_or(None)
+    }
+
+    /// The new vertices of an edge
+    pub fn arc(&self, vertices: Option<(usize, usize)>) -> Option<(usize, usize)> {
+        let (u, v) = vertices?;
+        Some((self.vertex(u)?, self.vertex(v)?))
+    }
+
+    pub fn vertices_size(&self) -> usize {
+        self.vertices_size
+    }
+
+    pub fn edges_size(&self) -> usize {
+        self.edges_size
+    }
+}
+
+/// What a graph does with a loop u->u
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfLoops {
+    /// adding a loop fails with `GraphError::SelfLoop`
+    Forbid,
+    /// a loop is an edge u->u. In an undirected graph, it is its own twin and counts twice in
+    /// the degree of u.
+    Allow,
+}
 
 pub struct BasicGraph<E>
     where E: EdgeSet<usize, usize>
@@ -29,23 +124,77 @@ pub struct BasicGraph<E>
     vertices: DenseVecIndices,
     edges: DenseVecIndices,
     adjacent_vertices: E,
+    // v => u | u -> v
+    reversed_adjacent_vertices: E,
     // u => v | u -> v
     edge_to_vertices: HashMap<usize, (usize, usize)>,
     // e = (u, v)
+    out_degree: Vec<usize>,
+    in_degree: Vec<usize>,
+    vertex_generations: Generations,
+    edge_generations: Generations,
+    self_loops: SelfLoops,
+}
+
+/// The generations of the slots of the vertices or of the edges. A slot that was never freed
+/// has the base generation.
+struct Generations {
+    slots: Vec<u32>,
+    base: u32,
+}
+
+impl Generations {
+    fn new() -> Generations {
+        Generations {
+            slots: Vec::new(),
+            base: 0,
+        }
+    }
+
+    fn get(&self, i: usize) -> u32 {
+        self.slots.get(i).cloned().unwrap_or(self.base)
+    }
+
+    /// A freed slot gets a new generation: the handles on the previous occupant become stale.
+    fn next(&mut self, i: usize) {
+        if self.slots.len() <= i {
+            self.slots.resize(i + 1, self.base);
+        }
+        self.slots[i] = self.slots[i].wrapping_add(1);
+    }
+
+    /// After a compaction, every slot, even beyond the current ones, gets a generation that no
+    /// handle has seen.
+    fn renew(&mut self) {
+        let max = self.slots.iter().cloned().fold(self.base, u32::max);
+        self.base = max.wrapping_add(1);
+        self.slots.clear();
+    }
 }
 
 /// Every vertex and every edge is identified by an index (usize).
 impl<ES> BasicGraph<ES>
     where ES: EdgeSet<usize, usize>
 {
+    /// A graph that allows loops
     pub fn new() -> BasicGraph<ES> {
+        BasicGraph::with_self_loops(SelfLoops::Allow)
+    }
+
+    pub fn with_self_loops(self_loops: SelfLoops) -> BasicGraph<ES> {
         BasicGraph {
             vertices: DenseVecIndices::new(),
             edges: DenseVecIndices::new(),
 
             adjacent_vertices: ES::new(),
+            reversed_adjacent_vertices: ES::new(),
 
             edge_to_vertices: HashMap::new(),
+            out_degree: Vec::new(),
+            in_degree: Vec::new(),
+            vertex_generations: Generations::new(),
+            edge_generations: Generations::new(),
+            self_loops,
         }
     }
 
@@ -54,27 +203,65 @@ impl<ES> BasicGraph<ES>
         self.vertices.index_consume()
     }
 
-    pub fn remove_vertex(&mut self, v: usize) {
+    /// Remove a vertex and the edges from or to this vertex
+    pub fn remove_vertex(&mut self, v: usize) -> Result<(), GraphError> {
+        if !self.vertices.index_is_used(v) {
+            return Err(GraphError::UnknownVertex(v));
+        }
+        // a loop v->v is both an out and an in edge of v
+        let mut incident_edges = self.adjacent_vertices.edges_from(&v);
+        incident_edges.extend(self.reversed_adjacent_vertices.edges_from(&v).into_iter()
+            .filter(|&e| self.edge_to_vertices[&e] != (v, v)));
+        for e in incident_edges {
+            self.remove_edge(e)?;
+        }
         self.vertices.free_index(v);
+        self.vertex_generations.next(v);
+        Ok(())
     }
 
-    /// Get a free index for an edge
-    pub fn add_edge(&mut self, u: usize, v: usize) -> usize {
+    /// Get a free index for an edge. The edge set may reject the edge (e.g. a second edge u->v):
+    /// then, no index is consumed.
+    pub fn add_edge(&mut self, u: usize, v: usize) -> Result<usize, GraphError> {
+        for &w in &[u, v] {
+            if !self.vertices.index_is_used(w) {
+                return Err(GraphError::UnknownVertex(w));
+            }
+        }
+        if u == v && self.self_loops == SelfLoops::Forbid {
+            return Err(GraphError::SelfLoop(u));
+        }
         let e = self.edges.index_consume();
-        self.adjacent_vertices.add_edge(u, v, e);
+        if !self.adjacent_vertices.add_edge(u, v, e) {
+            self.edges.free_index(e);
+            return Err(GraphError::DuplicateEdge(u, v));
+        }
+        self.reversed_adjacent_vertices.add_edge(v, u, e);
+        let n = self.vertices.max();
+        if self.out_degree.len() < n {
+            self.out_degree.resize(n, 0);
+            self.in_degree.resize(n, 0);
+        }
+        self.out_degree[u] += 1;
+        self.in_degree[v] += 1;
         self.edge_to_vertices.insert(e, (u, v));
-        e
+        Ok(e)
     }
 
-    pub fn remove_edge(&mut self, e: usize) {
-        if self.edges.free_index(e) {
-            self.edge_to_vertices.remove(&e);
+    pub fn remove_edge(&mut self, e: usize) -> Result<(), GraphError> {
+        let (u, v) = self.get_vertices_from_edge(e).ok_or(GraphError::UnknownEdge(e))?;
+        self.edges.free_index(e);
+        self.edge_generations.next(e);
+        self.edge_to_vertices.remove(&e);
+        self.adjacent_vertices.remove_edge(&u, &v, &e);
+        self.reversed_adjacent_vertices.remove_edge(&v, &u, &e);
+        self.out_degree[u] -= 1;
+        self.in_degree[v] -= 1;
+        Ok(())
+    }
 
-            match self.get_vertices_from_edge(e) {
-                None => {}
-                Some((u, v)) => { self.adjacent_vertices.remove_edge(&u, &v, &e); }
-            }
-        }
+    pub fn self_loops(&self) -> SelfLoops {
+        self.self_loops
     }
 
     pub fn get_edges_from_vertices(&self, u: usize, v: usize) -> Option<&ES::S> {
@@ -96,10 +283,25 @@ impl<ES> BasicGraph<ES>
         self.edges.used_indices_iter()
     }
 
-    pub fn direct_adjacent_vertices_iter(&self, u: usize) -> hash_map::Iter<usize, ES::S> {
+    pub fn direct_adjacent_vertices_iter(&self, u: usize) -> ES::EdgesByToIter<'_> {
         self.adjacent_vertices.edges_by_to_iter(&u)
     }
 
+    /// v => (u, edges) | u -> v
+    pub fn reversed_adjacent_vertices_iter(&self, v: usize) -> ES::EdgesByToIter<'_> {
+        self.reversed_adjacent_vertices.edges_by_to_iter(&v)
+    }
+
+    /// The number of edges u -> *
+    pub fn out_degree(&self, u: usize) -> usize {
+        self.out_degree.get(u).cloned().unwrap_or(0)
+    }
+
+    /// The number of edges * -> v
+    pub fn in_degree(&self, v: usize) -> usize {
+        self.in_degree.get(v).cloned().unwrap_or(0)
+    }
+
     pub fn vertices_size(&self) -> usize {
         self.vertices.size()
     }
@@ -115,4 +317,204 @@ impl<ES> BasicGraph<ES>
     pub fn edges_max(&self) -> usize {
         self.edges.max()
     }
+
+    /// Renumber the vertices and the edges from 0 and return the old -> new ids. The edges of
+    /// a removed vertex are dropped. Every handle becomes stale.
+    pub fn compact(&mut self) -> Compaction {
+        let compaction = {
+            let edge_to_vertices = &self.edge_to_vertices;
+            Compaction::new(&self.vertices, &self.edges, |e| edge_to_vertices.get(&e).cloned())
+        };
+        let n = compaction.vertices_size();
+        let mut out_degree = vec![0; n];
+        let mut in_degree = vec![0; n];
+        self.adjacent_vertices = self.adjacent_vertices.remap(|&u, &v, &e| {
+            let (u, v) = compaction.arc(Some((u, v)))?;
+            let e = compaction.edge(e)?;
+            out_degree[u] += 1;
+            in_degree[v] += 1;
+            Some((u, v, e))
+        });
+        self.reversed_adjacent_vertices = self.reversed_adjacent_vertices.remap(|&v, &u, &e| {
+            let (v, u) = compaction.arc(Some((v, u)))?;
+            Some((v, u, compaction.edge(e)?))
+        });
+        self.edge_to_vertices = self.edge_to_vertices.iter()
+            .filter_map(|(&e, &uv)| Some((compaction.edge(e)?, compaction.arc(Some(uv))?)))
+            .collect();
+        self.out_degree = out_degree;
+        self.in_degree = in_degree;
+        self.vertices = DenseVecIndices::new_dense(n);
+        self.edges = DenseVecIndices::new_dense(compaction.edges_size());
+        self.vertex_generations.renew();
+        self.edge_generations.renew();
+        compaction
+    }
+
+    /// A handle on the vertex `v`, or None if there is no such vertex.
+    pub fn vertex_handle(&self, v: usize) -> Option<VertexHandle> {
+        match self.vertices.index_is_used(v) {
+            true => Some(VertexHandle { index: v, generation: self.vertex_generations.get(v) }),
+            false => None,
+        }
+    }
+
+    /// The vertex of the handle, or None if the vertex was removed since.
+    pub fn vertex_from_handle(&self, h: VertexHandle) -> Option<usize> {
+        match self.vertex_handle(h.index) {
+            Some(cur) if cur == h => Some(h.index),
+            _ => None,
+        }
+    }
+
+    /// A handle on the edge `e`, or None if there is no such edge.
+    pub fn edge_handle(&self, e: usize) -> Option<EdgeHandle> {
+        match self.edges.index_is_used(e) {
+            true => Some(EdgeHandle { index: e, generation: self.edge_generations.get(e) }),
+            false => None,
+        }
+    }
+
+    /// The edge of the handle, or None if the edge was removed since.
+    pub fn edge_from_handle(&self, h: EdgeHandle) -> Option<usize> {
+        match self.edge_handle(h.index) {
+            Some(cur) if cur == h => Some(h.index),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use util::simple_edge_set::SimpleEdgeSet;
+
+    #[test]
+    fn test_vertex_handle() {
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::new();
+        let a = g.add_vertex();
+        let b = g.add_vertex();
+        let ha = g.vertex_handle(a).unwrap();
+        let hb = g.vertex_handle(b).unwrap();
+        assert_eq!(None, g.vertex_handle(2));
+
+        g.remove_vertex(a).unwrap();
+        assert_eq!(None, g.vertex_from_handle(ha));
+        assert_eq!(Some(b), g.vertex_from_handle(hb));
+
+        // the index is reused, but the old handle stays stale
+        assert_eq!(a, g.add_vertex());
+        assert_eq!(None, g.vertex_from_handle(ha));
+        let ha2 = g.vertex_handle(a).unwrap();
+        assert_ne!(ha, ha2);
+        assert_eq!(Some(a), g.vertex_from_handle(ha2));
+    }
+
+    #[test]
+    fn test_edge_handle() {
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::new();
+        let a = g.add_vertex();
+        let b = g.add_vertex();
+        let c = g.add_vertex();
+        let e = g.add_edge(a, b).unwrap();
+        let h = g.edge_handle(e).unwrap();
+        assert_eq!(Some(e), g.edge_from_handle(h));
+
+        g.remove_edge(e).unwrap();
+        assert_eq!(None, g.edge_handle(e));
+        assert_eq!(e, g.add_edge(b, c).unwrap());
+        assert_eq!(None, g.edge_from_handle(h));
+        assert_eq!(Some((b, c)), g.get_vertices_from_edge(g.edge_handle(e).unwrap().index()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::new();
+        let a = g.add_vertex();
+        let b = g.add_vertex();
+        assert_eq!(Err(GraphError::UnknownVertex(2)), g.add_edge(a, 2));
+        let e = g.add_edge(a, b).unwrap();
+        assert_eq!(Err(GraphError::DuplicateEdge(a, b)), g.add_edge(a, b));
+        // the rejected edges did not consume an index
+        assert_eq!(1, g.edges_max());
+        assert_eq!(Ok(()), g.remove_edge(e));
+        assert_eq!(Err(GraphError::UnknownEdge(e)), g.remove_edge(e));
+        let e = g.add_edge(b, a).unwrap();
+        g.add_edge(b, b).unwrap();
+        assert_eq!(Ok(()), g.remove_vertex(b));
+        assert_eq!(Err(GraphError::UnknownVertex(b)), g.remove_vertex(b));
+        // the edges of b are removed with b
+        assert_eq!(None, g.get_vertices_from_edge(e));
+        assert_eq!((0, 0), (g.edges_size(), g.in_degree(a)));
+    }
+
+    #[test]
+    fn test_self_loops() {
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::with_self_loops(SelfLoops::Forbid);
+        let a = g.add_vertex();
+        assert_eq!(Err(GraphError::SelfLoop(a)), g.add_edge(a, a));
+        assert_eq!(0, g.edges_max());
+
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::new();
+        let a = g.add_vertex();
+        let e = g.add_edge(a, a).unwrap();
+        assert_eq!((1, 1), (g.out_degree(a), g.in_degree(a)));
+        g.remove_edge(e).unwrap();
+        assert_eq!((0, 0), (g.out_degree(a), g.in_degree(a)));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::new();
+        for _ in 0..5 {
+            g.add_vertex();
+        }
+        let e01 = g.add_edge(0, 1).unwrap();
+        let e12 = g.add_edge(1, 2).unwrap();
+        let e23 = g.add_edge(2, 3).unwrap();
+        let e34 = g.add_edge(3, 4).unwrap();
+        let e40 = g.add_edge(4, 0).unwrap();
+        let h = g.vertex_handle(4).unwrap();
+        g.remove_edge(e01).unwrap();
+        g.remove_vertex(2).unwrap();
+
+        let compaction = g.compact();
+        assert_eq!(4, g.vertices_max());
+        assert_eq!(2, g.edges_max());
+        assert_eq!((Some(0), Some(1), None, Some(2), Some(3)),
+                   (compaction.vertex(0), compaction.vertex(1), compaction.vertex(2),
+                    compaction.vertex(3), compaction.vertex(4)));
+        // the edges of the removed vertex 2 are dropped
+        assert_eq!((None, None, None, Some(0), Some(1)),
+                   (compaction.edge(e01), compaction.edge(e12), compaction.edge(e23),
+                    compaction.edge(e34), compaction.edge(e40)));
+        assert_eq!(Some((2, 3)), g.get_vertices_from_edge(0));
+        assert_eq!(Some((3, 0)), g.get_vertices_from_edge(1));
+        assert_eq!(Some(&1), g.get_edges_from_vertices(3, 0));
+        assert_eq!(vec![(3, 1)], g.reversed_adjacent_vertices_iter(0).map(|(&u, &e)| (u, e)).collect::<Vec<_>>());
+        assert_eq!((0, 1, 1, 1), (g.out_degree(1), g.in_degree(3), g.out_degree(3), g.in_degree(0)));
+        assert_eq!(None, g.vertex_from_handle(h));
+    }
+
+    #[test]
+    fn test_compact_handles_beyond_the_new_ids() {
+        let mut g: BasicGraph<SimpleEdgeSet<usize, usize>> = BasicGraph::new();
+        for _ in 0..3 {
+            g.add_vertex();
+        }
+        let e01 = g.add_edge(0, 1).unwrap();
+        let e12 = g.add_edge(1, 2).unwrap();
+        let h2 = g.vertex_handle(2).unwrap();
+        let h12 = g.edge_handle(e12).unwrap();
+        g.remove_vertex(0).unwrap();
+        assert_eq!(None, g.get_vertices_from_edge(e01));
+        g.compact();
+        // the slots 2 and 1 are reused after the compaction, by a new vertex and a new edge
+        assert_eq!(2, g.add_vertex());
+        assert_eq!(1, g.add_edge(1, 0).unwrap());
+        assert_eq!(None, g.vertex_from_handle(h2));
+        assert_eq!(None, g.edge_from_handle(h12));
+        let h = g.vertex_handle(2).unwrap();
+        assert_eq!(Some(2), g.vertex_from_handle(h));
+    }
 }
@@ -0,0 +1,565 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::fmt::Debug;
+use std::iter::Cloned;
+use std::iter::Map;
+use std::iter::Zip;
+use std::slice;
+
+use graph::DecoratedGraph;
+use graph::DirectedGraph;
+use graph::Graph;
+use graph::UndirectedGraph;
+
+/// Compressed sparse rows: the arcs (u, v, e) sorted by u then v. The arcs of u are at
+/// `offsets[u]..offsets[u + 1]` in `targets` and `edges`.
+struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    edges: Vec<usize>,
+}
+
+impl Csr {
+    fn new(vertices_max: usize, arcs: &mut [(usize, usize, usize)]) -> Csr {
+        arcs.sort();
+        let mut offsets = vec![0; vertices_max + 1];
+        for &(u, _, _) in arcs.iter() {
+            offsets[u + 1] += 1;
+        }
+        for u in 0..vertices_max {
+            offsets[u + 1] += offsets[u];
+        }
+        Csr {
+            offsets,
+            targets: arcs.iter().map(|&(_, v, _)| v).collect(),
+            edges: arcs.iter().map(|&(_, _, e)| e).collect(),
+        }
+    }
+
+    fn range(&self, u: usize) -> (usize, usize) {
+        match self.offsets.get(u + 1) {
+            Some(&end) => (self.offsets[u], end),
+            None => (0, 0),
+        }
+    }
+
+    fn targets(&self, u: usize) -> &[usize] {
+        let (start, end) = self.range(u);
+        &self.targets[start..end]
+    }
+
+    fn edges_by_target_iter(&self, u: usize) -> Zip<slice::Iter<'_, usize>, slice::Iter<'_, usize>> {
+        let (start, end) = self.range(u);
+        self.targets[start..end].iter().zip(self.edges[start..end].iter())
+    }
+
+    fn edges_to(&self, u: usize, v: usize) -> &[usize] {
+        let (start, end) = self.range(u);
+        let targets = &self.targets[start..end];
+        let first = targets.partition_point(|&w| w < v);
+        let last = targets.partition_point(|&w| w <= v);
+        &self.edges[start + first..start + last]
+    }
+
+    fn degree(&self, u: usize) -> usize {
+        let (start, end) = self.range(u);
+        end - start
+    }
+}
+
+/// The data shared by the directed and undirected CSR graphs. The vertices and the edges keep
+/// the ids of the graph they were built from.
+struct CsrData {
+    vertices: Vec<usize>,
+    vertices_max: usize,
+    edges: Vec<usize>,
+    edge_to_vertices: Vec<Option<(usize, usize)>>,
+    weights: Vec<Option<usize>>,
+    out: Csr,
+}
+
+impl CsrData {
+    fn new(vertices: Vec<usize>, vertices_max: usize, mut arcs: Vec<(usize, usize, usize)>,
+           weights: Vec<Option<usize>>) -> CsrData {
+        let edges_max = arcs.iter().map(|&(_, _, e)| e + 1).max().unwrap_or(0).max(weights.len());
+        let mut edge_to_vertices = vec![None; edges_max];
+        let mut edges = Vec::with_capacity(arcs.len());
+        for &(u, v, e) in &arcs {
+            edge_to_vertices[e] = Some((u, v));
+            edges.push(e);
+        }
+        edges.sort();
+        CsrData {
+            vertices,
+            vertices_max,
+            edges,
+            edge_to_vertices,
+            weights,
+            out: Csr::new(vertices_max, &mut arcs),
+        }
+    }
+
+    fn from_graph<'b, G>(g: &'b G) -> (Vec<usize>, Vec<(usize, usize, usize)>)
+        where G: Graph<'b>
+    {
+        let vertices: Vec<usize> = g.vertices_iter().collect();
+        let mut arcs = Vec::with_capacity(g.edges_size());
+        for &u in &vertices {
+            for (&v, &e) in g.adjacent_edges_by_vertex_iter(u) {
+                arcs.push((u, v, e));
+            }
+        }
+        (vertices, arcs)
+    }
+
+    fn weights_of<'b, G, V>(g: &'b G, arcs: &[(usize, usize, usize)]) -> Vec<Option<usize>>
+        where G: DecoratedGraph<'b, V, &'b usize>,
+              V: 'b + PartialEq + Clone + Debug
+    {
+        let mut weights = vec![None; g.edges_max()];
+        for &(u, v, e) in arcs {
+            if let Some((_, Some(&w))) = g.edges_values_iter(u, v).find(|&(e2, _)| e2 == e) {
+                weights[e] = Some(w);
+            }
+        }
+        weights
+    }
+
+    fn edges_values_iter<'a>(&'a self, edges: &'a [usize]) -> CsrEdgesValuesIter<'a> {
+        CsrEdgesValuesIter {
+            edges: edges.iter(),
+            weights: &self.weights,
+        }
+    }
+}
+
+/// An iterator on edges and their weights, borrowed from a CSR graph
+pub struct CsrEdgesValuesIter<'a> {
+    edges: slice::Iter<'a, usize>,
+    weights: &'a [Option<usize>],
+}
+
+impl<'a> Iterator for CsrEdgesValuesIter<'a> {
+    type Item = (usize, Option<&'a usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &e = self.edges.next()?;
+        Some((e, self.weights.get(e).and_then(|o_w| o_w.as_ref())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.edges.size_hint()
+    }
+}
+
+/// An immutable directed graph, stored as compressed sparse rows for the outgoing and the
+/// incoming edges. The neighbors of a vertex are contiguous and sorted. Parallel edges are
+/// allowed. An edge may have a weight: the graph is then a `DecoratedGraph` with `&usize` edge
+/// values, usable by the weighted browsers.
+pub struct DirectedCsrGraph {
+    data: CsrData,
+    inc: Csr,
+}
+
+impl DirectedCsrGraph {
+    /// Copy a graph. The vertices and the edges keep their ids.
+    pub fn from_graph<'b, G>(g: &'b G) -> DirectedCsrGraph
+        where G: DirectedGraph<'b>
+    {
+        let (vertices, arcs) = CsrData::from_graph(g);
+        Self::new(vertices, g.vertices_max(), arcs, Vec::new())
+    }
+
+    /// Copy a graph and the edge weights
+    pub fn from_decorated_graph<'b, G, V>(g: &'b G) -> DirectedCsrGraph
+        where G: DirectedGraph<'b> + DecoratedGraph<'b, V, &'b usize>,
+              V: 'b + PartialEq + Clone + Debug
+    {
+        let (vertices, arcs) = CsrData::from_graph(g);
+        let weights = CsrData::weights_of(g, &arcs);
+        Self::new(vertices, g.vertices_max(), arcs, weights)
+    }
+
+    /// A graph with the vertices 0..vertices_count. The id of `edges[i]` is i.
+    pub fn from_edges(vertices_count: usize, edges: &[(usize, usize)]) -> DirectedCsrGraph {
+        let arcs = edges.iter().enumerate().map(|(e, &(u, v))| (u, v, e)).collect();
+        Self::new((0..vertices_count).collect(), vertices_count, arcs, Vec::new())
+    }
+
+    /// A graph with the vertices 0..vertices_count. The id of `edges[i]` is i.
+    pub fn from_weighted_edges(vertices_count: usize, edges: &[(usize, usize, usize)]) -> DirectedCsrGraph {
+        let arcs = edges.iter().enumerate().map(|(e, &(u, v, _))| (u, v, e)).collect();
+        let weights = edges.iter().map(|&(_, _, w)| Some(w)).collect();
+        Self::new((0..vertices_count).collect(), vertices_count, arcs, weights)
+    }
+
+    fn new(vertices: Vec<usize>, vertices_max: usize, arcs: Vec<(usize, usize, usize)>,
+           weights: Vec<Option<usize>>) -> DirectedCsrGraph {
+        let mut reversed_arcs: Vec<(usize, usize, usize)> = arcs.iter().map(|&(u, v, e)| (v, u, e)).collect();
+        DirectedCsrGraph {
+            inc: Csr::new(vertices_max, &mut reversed_arcs),
+            data: CsrData::new(vertices, vertices_max, arcs, weights),
+        }
+    }
+
+    /// The successors of u, sorted
+    pub fn neighbors(&self, u: usize) -> &[usize] {
+        self.data.out.targets(u)
+    }
+}
+
+/// An immutable undirected graph, stored as compressed sparse rows. As in
+/// `UndirectedSimpleGraphImpl`, an undirected edge is a couple of twin edges u->v and v->u.
+pub struct UndirectedCsrGraph {
+    data: CsrData,
+    reversed: Vec<Option<usize>>,
+}
+
+impl UndirectedCsrGraph {
+    /// Copy a graph. The vertices and the edges keep their ids.
+    pub fn from_graph<'b, G>(g: &'b G) -> UndirectedCsrGraph
+        where G: UndirectedGraph<'b>
+    {
+        let (vertices, arcs) = CsrData::from_graph(g);
+        let reversed = Self::reversed_of(g, &arcs);
+        Self::new(vertices, g.vertices_max(), arcs, Vec::new(), reversed)
+    }
+
+    /// Copy a graph and the edge weights
+    pub fn from_decorated_graph<'b, G, V>(g: &'b G) -> UndirectedCsrGraph
+        where G: UndirectedGraph<'b> + DecoratedGraph<'b, V, &'b usize>,
+              V: 'b + PartialEq + Clone + Debug
+    {
+        let (vertices, arcs) = CsrData::from_graph(g);
+        let weights = CsrData::weights_of(g, &arcs);
+        let reversed = Self::reversed_of(g, &arcs);
+        Self::new(vertices, g.vertices_max(), arcs, weights, reversed)
+    }
+
+    /// A graph with the vertices 0..vertices_count. The ids of the twin edges of `edges[i]` are
+    /// 2i (v->u) and 2i + 1 (u->v).
+    pub fn from_edges(vertices_count: usize, edges: &[(usize, usize)]) -> UndirectedCsrGraph {
+        let weighted: Vec<(usize, usize, usize)> = edges.iter().map(|&(u, v)| (u, v, 0)).collect();
+        let mut g = Self::from_weighted_edges(vertices_count, &weighted);
+        g.data.weights.clear();
+        g
+    }
+
+    /// A graph with the vertices 0..vertices_count. The ids of the twin edges of `edges[i]` are
+    /// 2i (v->u) and 2i + 1 (u->v). A loop u-u is the single edge 2i, its own twin.
+    pub fn from_weighted_edges(vertices_count: usize, edges: &[(usize, usize, usize)]) -> UndirectedCsrGraph {
+        let mut arcs = Vec::with_capacity(2 * edges.len());
+        let mut weights = Vec::with_capacity(2 * edges.len());
+        let mut reversed = Vec::with_capacity(2 * edges.len());
+        for (i, &(u, v, w)) in edges.iter().enumerate() {
+            if u == v {
+                arcs.push((u, u, 2 * i));
+                weights.push(Some(w));
+                weights.push(None);
+                reversed.push(Some(2 * i));
+                reversed.push(None);
+                continue;
+            }
+            arcs.push((v, u, 2 * i));
+            arcs.push((u, v, 2 * i + 1));
+            weights.push(Some(w));
+            weights.push(Some(w));
+            reversed.push(Some(2 * i + 1));
+            reversed.push(Some(2 * i));
+        }
+        Self::new((0..vertices_count).collect(), vertices_count, arcs, weights, reversed)
+    }
+
+    fn reversed_of<'b, G>(g: &'b G, arcs: &[(usize, usize, usize)]) -> Vec<Option<usize>>
+        where G: Graph<'b>
+    {
+        let mut reversed = vec![None; g.edges_max()];
+        for &(_, _, e) in arcs {
+            reversed[e] = g.get_reversed_edge(e);
+        }
+        reversed
+    }
+
+    fn new(vertices: Vec<usize>, vertices_max: usize, arcs: Vec<(usize, usize, usize)>,
+           weights: Vec<Option<usize>>, reversed: Vec<Option<usize>>) -> UndirectedCsrGraph {
+        UndirectedCsrGraph {
+            data: CsrData::new(vertices, vertices_max, arcs, weights),
+            reversed,
+        }
+    }
+
+    /// The neighbors of u, sorted
+    pub fn neighbors(&self, u: usize) -> &[usize] {
+        self.data.out.targets(u)
+    }
+
+    fn is_main_edge(&self, e: usize) -> bool {
+        match self.data.edge_to_vertices[e] {
+            None => false,
+            Some((u, v)) => u < v || u == v && self.reversed[e].map_or(true, |e2| e <= e2),
+        }
+    }
+}
+
+impl<'a> Graph<'a> for DirectedCsrGraph {
+    type VerticesIterator = Cloned<slice::Iter<'a, usize>>;
+    type EdgesIterator = Cloned<slice::Iter<'a, usize>>;
+    type EdgesFromVerticesIterator = Cloned<slice::Iter<'a, usize>>;
+    type AdjacentVerticesIterator = Cloned<slice::Iter<'a, usize>>;
+    type AdjacentEdgesByVerticesIterator = Zip<slice::Iter<'a, usize>, slice::Iter<'a, usize>>;
+
+    fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
+        self.data.edge_to_vertices.get(e).cloned().unwrap_or(None)
+    }
+
+    fn get_reversed_edge(&self, _: usize) -> Option<usize> {
+        None
+    }
+
+    fn vertices_size(&self) -> usize {
+        self.data.vertices.len()
+    }
+
+    fn vertices_max(&self) -> usize {
+        self.data.vertices_max
+    }
+
+    fn edges_size(&self) -> usize {
+        self.data.edges.len()
+    }
+
+    fn edges_max(&self) -> usize {
+        self.data.edge_to_vertices.len()
+    }
+
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+        self.data.out.edges_to(u, v).iter().cloned()
+    }
+
+    fn vertices_iter(&'a self) -> Self::VerticesIterator {
+        self.data.vertices.iter().cloned()
+    }
+
+    fn edges_iter(&'a self) -> Self::EdgesIterator {
+        self.data.edges.iter().cloned()
+    }
+
+    fn adjacent_vertices_iter(&'a self, u: usize) -> Self::AdjacentVerticesIterator {
+        self.data.out.targets(u).iter().cloned()
+    }
+
+    fn adjacent_edges_by_vertex_iter(&'a self, u: usize) -> Self::AdjacentEdgesByVerticesIterator {
+        self.data.out.edges_by_target_iter(u)
+    }
+}
+
+impl<'a> DecoratedGraph<'a, usize, &'a usize> for DirectedCsrGraph {
+    type VerticesValuesIterator = Map<<DirectedCsrGraph as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+    type EdgesValuesIterator = CsrEdgesValuesIter<'a>;
+
+    fn vertices_values_iter(&'a self) -> Self::VerticesValuesIterator {
+        self.vertices_iter().map(|i| (i, Some(i)))
+    }
+
+    fn edges_values_iter(&'a self, u: usize, v: usize) -> Self::EdgesValuesIterator {
+        self.data.edges_values_iter(self.data.out.edges_to(u, v))
+    }
+}
+
+impl<'a> DirectedGraph<'a> for DirectedCsrGraph {
+    type InNeighborsIterator = Cloned<slice::Iter<'a, usize>>;
+    type InEdgesIterator = Zip<slice::Iter<'a, usize>, slice::Iter<'a, usize>>;
+
+    fn in_neighbors_iter(&'a self, v: usize) -> Self::InNeighborsIterator {
+        self.inc.targets(v).iter().cloned()
+    }
+
+    fn in_edges_iter(&'a self, v: usize) -> Self::InEdgesIterator {
+        self.inc.edges_by_target_iter(v)
+    }
+
+    fn in_degree(&self, v: usize) -> usize {
+        self.inc.degree(v)
+    }
+
+    fn out_degree(&self, u: usize) -> usize {
+        self.data.out.degree(u)
+    }
+}
+
+impl<'a> Graph<'a> for UndirectedCsrGraph {
+    type VerticesIterator = Cloned<slice::Iter<'a, usize>>;
+    type EdgesIterator = Box<dyn Iterator<Item=usize> + 'a>; // as in UndirectedSimpleGraphImpl
+    type EdgesFromVerticesIterator = Cloned<slice::Iter<'a, usize>>;
+    type AdjacentVerticesIterator = Cloned<slice::Iter<'a, usize>>;
+    type AdjacentEdgesByVerticesIterator = Zip<slice::Iter<'a, usize>, slice::Iter<'a, usize>>;
+
+    fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
+        self.data.edge_to_vertices.get(e).cloned().unwrap_or(None)
+    }
+
+    fn get_reversed_edge(&self, e: usize) -> Option<usize> {
+        self.reversed.get(e).cloned().unwrap_or(None)
+    }
+
+    fn vertices_size(&self) -> usize {
+        self.data.vertices.len()
+    }
+
+    fn vertices_max(&self) -> usize {
+        self.data.vertices_max
+    }
+
+    fn edges_size(&self) -> usize {
+        self.data.edges.len()
+    }
+
+    fn edges_max(&self) -> usize {
+        self.data.edge_to_vertices.len()
+    }
+
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+        self.data.out.edges_to(u, v).iter().cloned()
+    }
+
+    fn vertices_iter(&'a self) -> Self::VerticesIterator {
+        self.data.vertices.iter().cloned()
+    }
+
+    fn edges_iter(&'a self) -> Self::EdgesIterator {
+        Box::new(self.data.edges.iter().cloned().filter(move |&e| self.is_main_edge(e)))
+    }
+
+    fn adjacent_vertices_iter(&'a self, u: usize) -> Self::AdjacentVerticesIterator {
+        self.data.out.targets(u).iter().cloned()
+    }
+
+    fn adjacent_edges_by_vertex_iter(&'a self, u: usize) -> Self::AdjacentEdgesByVerticesIterator {
+        self.data.out.edges_by_target_iter(u)
+    }
+}
+
+impl<'a> DecoratedGraph<'a, usize, &'a usize> for UndirectedCsrGraph {
+    type VerticesValuesIterator = Map<<UndirectedCsrGraph as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+    type EdgesValuesIterator = CsrEdgesValuesIter<'a>;
+
+    fn vertices_values_iter(&'a self) -> Self::VerticesValuesIterator {
+        self.vertices_iter().map(|i| (i, Some(i)))
+    }
+
+    fn edges_values_iter(&'a self, u: usize, v: usize) -> Self::EdgesValuesIterator {
+        self.data.edges_values_iter(self.data.out.edges_to(u, v))
+    }
+}
+
+impl<'a> UndirectedGraph<'a> for UndirectedCsrGraph {
+    /// A loop counts twice.
+    fn degree(&self, u: usize) -> usize {
+        self.data.out.degree(u) + self.data.out.edges_to(u, u).len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use algorithm::bfs::BFSBrowser;
+    use algorithm::dijkstra::DijkstraBrowser;
+    use algorithm::single_source_shortest_paths::SingleSourceShortestPaths;
+    use graph::basic_graph::BasicGraph;
+    use graph::DirectedSimpleGraphImpl;
+    use graph::UndirectedSimpleGraphImpl;
+    use graph::GraphBuilder;
+    use graph::examples::decorated_graph1;
+    use graph::examples::graph1;
+
+    use super::*;
+
+    #[test]
+    fn test_from_graph() {
+        let g = graph1::<DirectedSimpleGraphImpl>();
+        let csr = DirectedCsrGraph::from_graph(&g);
+        assert_eq!(g.vertices_size(), csr.vertices_size());
+        assert_eq!(g.edges_size(), csr.edges_size());
+        for u in g.vertices_iter() {
+            let mut neighbors: Vec<usize> = g.adjacent_vertices_iter(u).collect();
+            neighbors.sort();
+            assert_eq!(&neighbors[..], csr.neighbors(u));
+            assert_eq!(g.in_degree(u), csr.in_degree(u));
+        }
+        for e in g.edges_iter() {
+            let (u, v) = g.get_vertices_from_edge(e).expect("an edge");
+            assert_eq!(Some((u, v)), csr.get_vertices_from_edge(e));
+            assert_eq!(vec![e], csr.get_edges_from_vertices_iter(u, v).collect::<Vec<usize>>());
+        }
+        assert_eq!(vec![0, 4], csr.in_neighbors_iter(5).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_browsers() {
+        let csr = DirectedCsrGraph::from_edges(4, &[(0, 1), (1, 2), (0, 2), (2, 3)]);
+        let mut marked_vertices: Vec<Vec<usize>> = Vec::new();
+        BFSBrowser::new(&csr, &mut marked_vertices).browse_from(0);
+        assert_eq!(vec![vec![0], vec![1], vec![2], vec![3]], marked_vertices);
+
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let dg = decorated_graph1(&mut g);
+        let csr = DirectedCsrGraph::from_decorated_graph(&dg);
+        let mut visitor: Vec<Vec<usize>> = Vec::new();
+        let mut b = DijkstraBrowser::new(&csr, 0, csr.vertices_max(), &mut visitor);
+        let paths = b.browse();
+        assert_eq!(Some(9), paths.dist(6));
+        assert_eq!(vec![0, 2, 3, 4, 6], paths.path(6));
+    }
+
+    #[test]
+    fn test_undirected() {
+        let csr = UndirectedCsrGraph::from_weighted_edges(5, &[(0, 1, 3), (1, 2, 1), (2, 0, 1), (2, 3, 4)]);
+        assert_eq!(8, csr.edges_size());
+        assert_eq!(4, csr.edges_iter().count());
+        assert_eq!(3, csr.degree(2));
+        assert_eq!(0, csr.degree(4));
+        assert_eq!(&[0, 1, 3], csr.neighbors(2));
+        assert_eq!(Some(1), csr.get_reversed_edge(0));
+        assert_eq!(Some((0, 1)), csr.get_vertices_from_edge(1));
+        assert_eq!(vec![(4, Some(&1))], csr.edges_values_iter(0, 2).collect::<Vec<(usize, Option<&usize>)>>());
+        let mut visitor: Vec<Vec<usize>> = Vec::new();
+        let mut b = DijkstraBrowser::new(&csr, 0, 5, &mut visitor);
+        assert_eq!(Some(2), b.browse().dist(1));
+    }
+
+    #[test]
+    fn test_undirected_loop() {
+        let csr = UndirectedCsrGraph::from_edges(2, &[(0, 1), (1, 1)]);
+        assert_eq!(3, csr.edges_size());
+        assert_eq!(2, csr.edges_iter().count());
+        assert_eq!(3, csr.degree(1));
+        assert_eq!(Some(2), csr.get_reversed_edge(2));
+        assert_eq!(&[0, 1], csr.neighbors(1));
+
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        g.create_vertex();
+        g.create_vertex();
+        g.add_edge(0, 1);
+        let e = g.add_edge(0, 0);
+        let csr = UndirectedCsrGraph::from_graph(&g);
+        assert_eq!(3, csr.degree(0));
+        assert_eq!(Some(e), csr.get_reversed_edge(e));
+        assert_eq!(2, csr.edges_iter().count());
+    }
+}
@@ -17,28 +17,45 @@
 /// You should have received a copy of the GNU General Public License
 /// along with this program.  If not, see <http://www.gnu.org/licenses/>.
 /// ***************************************************************************
-use std::collections::hash_map;
 use std::iter::Map;
 use std::iter;
 
+use util::edge_set::EdgeSet;
 use util::simple_edge_set::SimpleEdgeSet;
+use util::vec_edge_set::VecEdgeSet;
 use util::dense_vec_indices::UsedIndicesIter;
 use util::iterator_util::EmptyOrOnceIter;
 use graph::Graph;
 use graph::DirectedGraph;
 use graph::GraphBuilder;
+use graph::GraphError;
 use graph::DecoratedGraph;
 use graph::basic_graph::BasicGraph;
+use graph::basic_graph::Compaction;
+use graph::basic_graph::SelfLoops;
+use graph::basic_graph::EdgeHandle;
+use graph::basic_graph::VertexHandle;
 
-pub struct DirectedSimpleGraphImpl {
-    basic_graph: BasicGraph<SimpleEdgeSet<usize, usize>>,
+/// A directed graph with at most one edge u->v. The edge set `ES` stores the adjacency.
+pub struct DirectedGraphImpl<ES>
+    where ES: EdgeSet<usize, usize, S = usize>
+{
+    basic_graph: BasicGraph<ES>,
 }
 
-impl<'a> GraphBuilder<'a> for DirectedSimpleGraphImpl {
-    type ES = SimpleEdgeSet<usize, usize>;
+/// The adjacency is a map: the neighbors are iterated in an arbitrary order.
+pub type DirectedSimpleGraphImpl = DirectedGraphImpl<SimpleEdgeSet<usize, usize>>;
 
-    fn new(basic_graph: BasicGraph<SimpleEdgeSet<usize, usize>>) -> DirectedSimpleGraphImpl {
-        DirectedSimpleGraphImpl {
+/// The adjacency is a Vec per vertex: the neighbors are iterated in insertion order.
+pub type DirectedVecGraphImpl = DirectedGraphImpl<VecEdgeSet<usize, usize>>;
+
+impl<'a, ES> GraphBuilder<'a> for DirectedGraphImpl<ES>
+    where ES: EdgeSet<usize, usize, S = usize>
+{
+    type ES = ES;
+
+    fn new(basic_graph: BasicGraph<ES>) -> DirectedGraphImpl<ES> {
+        DirectedGraphImpl {
             basic_graph,
         }
     }
@@ -47,25 +64,35 @@ impl<'a> GraphBuilder<'a> for DirectedSimpleGraphImpl {
         self.basic_graph.add_vertex()
     }
 
-    fn remove_vertex(&mut self, u: usize) {
-        self.basic_graph.remove_vertex(u);
+    fn self_loops(&self) -> SelfLoops {
+        self.basic_graph.self_loops()
+    }
+
+    fn try_remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        self.basic_graph.remove_vertex(u)
     }
 
-    fn add_edge(&mut self, u: usize, v: usize) -> usize {
+    fn try_add_edge(&mut self, u: usize, v: usize) -> Result<usize, GraphError> {
         self.basic_graph.add_edge(u, v)
     }
 
-    fn remove_edge(&mut self, e: usize) {
-        self.basic_graph.remove_edge(e);
+    fn try_remove_edge(&mut self, e: usize) -> Result<(), GraphError> {
+        self.basic_graph.remove_edge(e)
+    }
+
+    fn compact(&mut self) -> Compaction {
+        self.basic_graph.compact()
     }
 }
 
-impl<'a> Graph<'a> for DirectedSimpleGraphImpl {
+impl<'a, ES> Graph<'a> for DirectedGraphImpl<ES>
+    where ES: 'a + EdgeSet<usize, usize, S = usize>
+{
     type VerticesIterator = UsedIndicesIter<'a>;
     type EdgesIterator = UsedIndicesIter<'a>;
     type EdgesFromVerticesIterator = EmptyOrOnceIter;
-    type AdjacentVerticesIterator = Map<hash_map::Iter<'a, usize, usize>, fn((&usize, &usize)) -> usize>;
-    type AdjacentEdgesByVerticesIterator = hash_map::Iter<'a, usize, usize>;
+    type AdjacentVerticesIterator = Map<ES::EdgesByToIter<'a>, fn((&usize, &usize)) -> usize>;
+    type AdjacentEdgesByVerticesIterator = ES::EdgesByToIter<'a>;
 
     fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
         self.basic_graph.get_vertices_from_edge(e)
@@ -91,7 +118,7 @@ impl<'a> Graph<'a> for DirectedSimpleGraphImpl {
         self.basic_graph.edges_max()
     }
 
-    fn get_edges_from_vertices_iter(&self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
         match self.basic_graph.get_edges_from_vertices(u, v) {
             None => EmptyOrOnceIter::UEmpty(iter::empty()),
             Some(oe) => EmptyOrOnceIter::UOnce(iter::once(*oe)),
@@ -113,9 +140,11 @@ impl<'a> Graph<'a> for DirectedSimpleGraphImpl {
     }
 }
 
-impl<'a> DecoratedGraph<'a, usize, usize> for DirectedSimpleGraphImpl {
-    type VerticesValuesIterator = Map<<DirectedSimpleGraphImpl as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
-    type EdgesValuesIterator = Map<<DirectedSimpleGraphImpl as Graph<'a>>::EdgesFromVerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+impl<'a, ES> DecoratedGraph<'a, usize, usize> for DirectedGraphImpl<ES>
+    where ES: 'a + EdgeSet<usize, usize, S = usize>
+{
+    type VerticesValuesIterator = Map<<Self as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+    type EdgesValuesIterator = Map<<Self as Graph<'a>>::EdgesFromVerticesIterator, fn(usize) -> (usize, Option<usize>)>;
 
     fn vertices_values_iter(&'a self) -> Self::VerticesValuesIterator {
         self.vertices_iter().map(|i| (i, Some(i)))
@@ -126,12 +155,58 @@ impl<'a> DecoratedGraph<'a, usize, usize> for DirectedSimpleGraphImpl {
     }
 }
 
-impl<'a> DirectedGraph<'a> for DirectedSimpleGraphImpl {}
+impl<'a, ES> DirectedGraph<'a> for DirectedGraphImpl<ES>
+    where ES: 'a + EdgeSet<usize, usize, S = usize>
+{
+    type InNeighborsIterator = Map<ES::EdgesByToIter<'a>, fn((&usize, &usize)) -> usize>;
+    type InEdgesIterator = ES::EdgesByToIter<'a>;
+
+    fn in_neighbors_iter(&'a self, v: usize) -> Self::InNeighborsIterator {
+        self.basic_graph.reversed_adjacent_vertices_iter(v).map(|(&u, _)| u)
+    }
+
+    fn in_edges_iter(&'a self, v: usize) -> Self::InEdgesIterator {
+        self.basic_graph.reversed_adjacent_vertices_iter(v)
+    }
+
+    fn in_degree(&self, v: usize) -> usize {
+        self.basic_graph.in_degree(v)
+    }
+
+    fn out_degree(&self, u: usize) -> usize {
+        self.basic_graph.out_degree(u)
+    }
+}
+
+impl<ES> DirectedGraphImpl<ES>
+    where ES: EdgeSet<usize, usize, S = usize>
+{
+    /// A handle on the vertex `v` that detects a later removal of `v`.
+    pub fn vertex_handle(&self, v: usize) -> Option<VertexHandle> {
+        self.basic_graph.vertex_handle(v)
+    }
+
+    pub fn vertex_from_handle(&self, h: VertexHandle) -> Option<usize> {
+        self.basic_graph.vertex_from_handle(h)
+    }
+
+    /// A handle on the edge `e` that detects a later removal of `e`.
+    pub fn edge_handle(&self, e: usize) -> Option<EdgeHandle> {
+        self.basic_graph.edge_handle(e)
+    }
+
+    pub fn edge_from_handle(&self, h: EdgeHandle) -> Option<usize> {
+        self.basic_graph.edge_from_handle(h)
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
     use graph::basic_graph::BasicGraph;
+    use graph::examples::decorated_graph1;
+    use util::GraphvizBuilder;
+    use util::GraphvizBuilderDirectedImpl;
 
     #[test]
     fn test_adj() {
@@ -149,4 +224,120 @@ mod test {
         g.add_edge(3, 5);
         g.add_edge(4, 5);
     }
+
+    #[test]
+    fn test_in_edges() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..4 {
+            g.create_vertex();
+        }
+        g.add_edge(0, 2);
+        let e = g.add_edge(1, 2);
+        g.add_edge(2, 3);
+        let mut in_neighbors: Vec<usize> = g.in_neighbors_iter(2).collect();
+        in_neighbors.sort();
+        assert_eq!(vec![0, 1], in_neighbors);
+        assert_eq!((2, 1), (g.in_degree(2), g.out_degree(2)));
+        assert_eq!(0, g.in_degree(0));
+
+        g.remove_edge(e);
+        assert_eq!(vec![(&0, &0)], g.in_edges_iter(2).collect::<Vec<(&usize, &usize)>>());
+        assert_eq!(1, g.in_degree(2));
+        assert_eq!(0, g.out_degree(1));
+        assert!(g.adjacent_vertices_iter(1).next().is_none());
+    }
+
+    #[test]
+    fn test_try_add_edge() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let a = g.create_vertex();
+        let b = g.create_vertex();
+        let e = g.add_edge(a, b);
+        assert_eq!(Err(GraphError::DuplicateEdge(a, b)), g.try_add_edge(a, b));
+        assert_eq!(Err(GraphError::UnknownVertex(5)), g.try_add_edge(5, b));
+        assert_eq!(1, g.edges_size());
+        assert_eq!(vec![e], g.edges_iter().collect::<Vec<usize>>());
+        assert!(g.try_add_edge(a, a).is_ok());
+        g.remove_edge(e);
+        assert_eq!(Err(GraphError::UnknownEdge(e)), g.try_remove_edge(e));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate edge 0->1")]
+    fn test_add_edge_panics() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        g.create_vertex();
+        g.create_vertex();
+        g.add_edge(0, 1);
+        g.add_edge(0, 1);
+    }
+
+    #[test]
+    fn test_stale_handles() {
+        let mut g = DirectedSimpleGraphImpl::new(BasicGraph::new());
+        let a = g.create_vertex();
+        let b = g.create_vertex();
+        let e = g.add_edge(a, b);
+        let ha = g.vertex_handle(a).unwrap();
+        let he = g.edge_handle(e).unwrap();
+
+        g.remove_edge(e);
+        g.remove_vertex(a);
+        assert_eq!(a, g.create_vertex());
+        assert_eq!(e, g.add_edge(a, b));
+        assert_eq!(None, g.vertex_from_handle(ha));
+        assert_eq!(None, g.edge_from_handle(he));
+        assert_eq!(Some(b), g.vertex_handle(b).and_then(|h| g.vertex_from_handle(h)));
+    }
+
+    #[test]
+    fn test_compact_keeps_insertion_order() {
+        let mut g = DirectedVecGraphImpl::new(BasicGraph::new());
+        for _ in 0..5 {
+            g.create_vertex();
+        }
+        let e = g.add_edge(0, 1);
+        g.add_edge(2, 4);
+        g.add_edge(2, 3);
+        g.remove_edge(e);
+        g.add_edge(2, 0);
+        g.remove_vertex(1);
+        let compaction = g.compact();
+        assert_eq!(4, g.vertices_max());
+        assert_eq!(3, g.edges_max());
+        assert_eq!(Some(1), compaction.vertex(2));
+        assert_eq!(vec![3, 2, 0], g.adjacent_vertices_iter(1).collect::<Vec<usize>>());
+        assert_eq!(vec![1], g.in_neighbors_iter(0).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_vec_insertion_order() {
+        let mut g = DirectedVecGraphImpl::new(BasicGraph::new());
+        for _ in 0..5 {
+            g.create_vertex();
+        }
+        g.add_edge(0, 3);
+        g.add_edge(0, 1);
+        let e = g.add_edge(0, 4);
+        g.add_edge(0, 2);
+        assert_eq!(vec![3, 1, 4, 2], g.adjacent_vertices_iter(0).collect::<Vec<usize>>());
+        g.remove_edge(e);
+        assert_eq!(vec![3, 1, 2], g.adjacent_vertices_iter(0).collect::<Vec<usize>>());
+        assert_eq!(3, g.out_degree(0));
+    }
+
+    #[test]
+    fn test_vec_graphviz_is_deterministic() {
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let mut g = DirectedVecGraphImpl::new(BasicGraph::new());
+            let dg = decorated_graph1(&mut g);
+            let marked_vertices = Vec::new();
+            outputs.push(GraphvizBuilderDirectedImpl::new(&dg, &marked_vertices).build_string());
+        }
+        assert_eq!(outputs[0], outputs[1]);
+        let ab = outputs[0].find("\"0_0\" -> \"0_1\"").expect("A -> B");
+        let ac = outputs[0].find("\"0_0\" -> \"0_2\"").expect("A -> C");
+        assert!(ab < ac);
+    }
 }
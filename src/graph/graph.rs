@@ -44,7 +44,7 @@ pub trait Graph<'a> {
     fn edges_max(&self) -> usize;
 
     /// return an iterator on edges. The iterator may be empty
-    fn get_edges_from_vertices_iter(&self, usize, usize) -> Self::EdgesFromVerticesIterator;
+    fn get_edges_from_vertices_iter(&'a self, usize, usize) -> Self::EdgesFromVerticesIterator;
 
     /// return an Iterator on vertices
     fn vertices_iter(&'a self) -> Self::VerticesIterator;
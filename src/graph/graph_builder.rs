@@ -19,6 +19,9 @@
 /// ***************************************************************************
 use util::edge_set::EdgeSet;
 use graph::basic_graph::BasicGraph;
+use graph::basic_graph::Compaction;
+use graph::basic_graph::SelfLoops;
+use graph::GraphError;
 
 pub trait GraphBuilder<'a> {
     type ES: EdgeSet<usize, usize>;
@@ -29,12 +32,33 @@ pub trait GraphBuilder<'a> {
     /// create a vertex
     fn create_vertex(&mut self) -> usize;
 
-    /// remove a vertex
-    fn remove_vertex(&mut self, u: usize);
+    /// tell if the graph accepts loops u->u (set by the `BasicGraph` given to `new`)
+    fn self_loops(&self) -> SelfLoops;
 
-    /// add an edge between u and v, and return a number
-    fn add_edge(&mut self, u: usize, v: usize) -> usize;
+    /// remove a vertex, or fail if there is no such vertex
+    fn try_remove_vertex(&mut self, u: usize) -> Result<(), GraphError>;
 
-    /// remove an edge
-    fn remove_edge(&mut self, e: usize);
+    /// add an edge between u and v and return a number, or fail if the graph rejects the edge
+    fn try_add_edge(&mut self, u: usize, v: usize) -> Result<usize, GraphError>;
+
+    /// remove an edge, or fail if there is no such edge
+    fn try_remove_edge(&mut self, e: usize) -> Result<(), GraphError>;
+
+    /// remove a vertex. Panics on error
+    fn remove_vertex(&mut self, u: usize) {
+        self.try_remove_vertex(u).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// add an edge between u and v, and return a number. Panics on error
+    fn add_edge(&mut self, u: usize, v: usize) -> usize {
+        self.try_add_edge(u, v).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// remove an edge. Panics on error
+    fn remove_edge(&mut self, e: usize) {
+        self.try_remove_edge(e).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// renumber the vertices and the edges without holes, and return the old -> new ids
+    fn compact(&mut self) -> Compaction;
 }
@@ -18,25 +18,137 @@
 /// You should have received a copy of the GNU General Public License
 /// along with this program.  If not, see <http://www.gnu.org/licenses/>.
 /// ***************************************************************************
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::Deref;
+use std::ops::DerefMut;
 
 use util::DenseVec;
 
+use graph::basic_graph::Compaction;
 use graph::DecoratedGraph;
 use graph::DirectedGraph;
 use graph::Graph;
 use graph::GraphBuilder;
+use graph::GraphError;
 use graph::UndirectedGraph;
 
-/// TODO
+/// The graph of a decorator: either borrowed or owned.
+enum GraphStorage<'a, G> where G: 'a {
+    Borrowed(&'a mut G),
+    Owned(G),
+}
+
+impl<'a, G> Deref for GraphStorage<'a, G> {
+    type Target = G;
+
+    fn deref(&self) -> &G {
+        match *self {
+            GraphStorage::Borrowed(ref g) => g,
+            GraphStorage::Owned(ref g) => g,
+        }
+    }
+}
+
+impl<'a, G> DerefMut for GraphStorage<'a, G> {
+    fn deref_mut(&mut self) -> &mut G {
+        match *self {
+            GraphStorage::Borrowed(ref mut g) => g,
+            GraphStorage::Owned(ref mut g) => g,
+        }
+    }
+}
+
+/// An index vertex value -> vertices. It is built only for hashable values (see
+/// `GraphDecorator::enable_value_index`).
+trait ValueIndex<V> {
+    fn index(&mut self, value: &V, u: usize);
+
+    fn unindex(&mut self, value: &V, u: usize);
+
+    /// The smallest vertex with the value
+    fn lookup(&self, value: &V) -> Option<usize>;
+
+    fn clear(&mut self);
+}
+
+/// The vertices with a value are kept in increasing order.
+impl<V> ValueIndex<V> for HashMap<V, Vec<usize>> where V: Hash + Eq + Clone {
+    fn index(&mut self, value: &V, u: usize) {
+        let vertices = self.entry(value.clone()).or_default();
+        if let Err(i) = vertices.binary_search(&u) {
+            vertices.insert(i, u);
+        }
+    }
+
+    fn unindex(&mut self, value: &V, u: usize) {
+        let empty = match self.get_mut(value) {
+            Some(vertices) => {
+                vertices.retain(|&v| v != u);
+                vertices.is_empty()
+            }
+            None => false,
+        };
+        if empty {
+            self.remove(value);
+        }
+    }
+
+    fn lookup(&self, value: &V) -> Option<usize> {
+        self.get(value).and_then(|vertices| vertices.first().cloned())
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+}
+
+type BoxedValueIndex<'a, V> = Box<dyn ValueIndex<V> + 'a>;
+
+/// A mutable reference on the value of a vertex. When it is dropped, the index of the values is
+/// updated.
+pub struct VertexValueMut<'b, 'a: 'b, V> where V: 'a {
+    u: usize,
+    value: &'b mut V,
+    // the value before any change and the index to update
+    reindex: Option<(V, &'b mut BoxedValueIndex<'a, V>)>,
+}
+
+impl<'b, 'a, V> Deref for VertexValueMut<'b, 'a, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        self.value
+    }
+}
+
+impl<'b, 'a, V> DerefMut for VertexValueMut<'b, 'a, V> {
+    fn deref_mut(&mut self) -> &mut V {
+        self.value
+    }
+}
+
+impl<'b, 'a, V> Drop for VertexValueMut<'b, 'a, V> {
+    fn drop(&mut self) {
+        if let Some((ref old_value, ref mut index)) = self.reindex {
+            index.unindex(old_value, self.u);
+            index.index(self.value, self.u);
+        }
+    }
+}
+
+/// A graph with a value on every vertex and every edge. The twin edges of an undirected edge
+/// share a single value.
 pub struct GraphDecorator<'a, G, V, E>
     where G: 'a + Graph<'a>,
           V: 'a + PartialEq + Clone + Debug,
           E: 'a + PartialEq + Clone + Debug
 {
-    graph: &'a mut G,
+    graph: GraphStorage<'a, G>,
     vertex_decorations: DenseVec<'a, V>,
     edge_decorations: DenseVec<'a, E>,
+    value_index: Option<BoxedValueIndex<'a, V>>,
 }
 
 impl<'a, G, V, E> GraphDecorator<'a, G, V, E>
@@ -44,26 +156,184 @@ impl<'a, G, V, E> GraphDecorator<'a, G, V, E>
           V: 'a + PartialEq + Clone + Debug,
           E: 'a + PartialEq + Clone + Debug
 {
+    /// Decorate a borrowed graph
     pub fn new(graph: &'a mut G) -> GraphDecorator<'a, G, V, E> {
+        GraphDecorator::with_storage(GraphStorage::Borrowed(graph))
+    }
+
+    /// Decorate a graph and take ownership of it
+    pub fn owning(graph: G) -> GraphDecorator<'a, G, V, E> {
+        GraphDecorator::with_storage(GraphStorage::Owned(graph))
+    }
+
+    fn with_storage(graph: GraphStorage<'a, G>) -> GraphDecorator<'a, G, V, E> {
         GraphDecorator {
             graph,
             vertex_decorations: DenseVec::new(),
             edge_decorations: DenseVec::new(),
+            value_index: None,
         }
     }
 
+    /// The decorated graph
+    pub fn graph(&self) -> &G {
+        &self.graph
+    }
+
     pub fn add_vertex(&mut self, vertex_value: V) -> usize {
         let v = self.graph.create_vertex();
-        self.vertex_decorations.add_value_at_place(v, vertex_value);
+        if let Some(ref mut index) = self.value_index {
+            index.index(&vertex_value, v);
+        }
+        self.vertex_decorations.set_value(v, vertex_value);
         v
     }
 
-    pub fn add_edge(&mut self, v1: usize, v2: usize, edge_value: E) {
-        let e1 = self.graph.add_edge(v1, v2);
-        self.edge_decorations.add_value_at_place(e1, edge_value.clone());
-        match self.graph.get_reversed_edge(e1) {
-            None => {}
-            Some(e2) => { self.edge_decorations.add_value_at_place(e2, edge_value); }
+    /// Set the value of a vertex, even if the vertex was not created through the decorator.
+    pub fn set_vertex_value(&mut self, u: usize, vertex_value: V) {
+        if let Some(ref mut index) = self.value_index {
+            if let Some(old_value) = self.vertex_decorations.get_value(u) {
+                index.unindex(old_value, u);
+            }
+            index.index(&vertex_value, u);
+        }
+        self.vertex_decorations.set_value(u, vertex_value);
+    }
+
+    pub fn vertex_value(&self, u: usize) -> Option<&V> {
+        self.vertex_decorations.get_value(u)
+    }
+
+    pub fn vertex_value_mut<'b>(&'b mut self, u: usize) -> Option<VertexValueMut<'b, 'a, V>> {
+        let value = self.vertex_decorations.get_mut_value(u)?;
+        let reindex = self.value_index.as_mut().map(|index| (value.clone(), index));
+        Some(VertexValueMut { u, value, reindex })
+    }
+
+    /// The smallest vertex with the given value. Without an index (see `enable_value_index`),
+    /// the vertices are scanned.
+    pub fn vertex_by_value(&self, vertex_value: &V) -> Option<usize> {
+        match self.value_index {
+            Some(ref index) => index.lookup(vertex_value),
+            None => self.vertex_decorations.indexed_values_iter()
+                .find(|&(_, value)| value == vertex_value)
+                .map(|(u, _)| u),
+        }
+    }
+
+    /// Add an edge v1->v2 and return its id (see `GraphBuilder::add_edge`)
+    pub fn add_edge(&mut self, v1: usize, v2: usize, edge_value: E) -> usize {
+        let e = self.graph.add_edge(v1, v2);
+        self.set_edge_value(e, edge_value);
+        e
+    }
+
+    /// Set the value of an edge (and of its twin), even if the edge was not created through the
+    /// decorator.
+    pub fn set_edge_value(&mut self, e: usize, edge_value: E) {
+        let key = self.edge_key(e);
+        self.edge_decorations.set_value(key, edge_value);
+    }
+
+    pub fn edge_value(&self, e: usize) -> Option<&E> {
+        self.edge_decorations.get_value(self.edge_key(e))
+    }
+
+    pub fn edge_value_mut(&mut self, e: usize) -> Option<&mut E> {
+        let key = self.edge_key(e);
+        self.edge_decorations.get_mut_value(key)
+    }
+
+    /// Remove a vertex, its edges and their values (see `GraphBuilder::try_remove_vertex`)
+    pub fn try_remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        self.graph.try_remove_vertex(u)?;
+        if let (Some(index), Some(value)) = (self.value_index.as_mut(), self.vertex_decorations.get_value(u)) {
+            index.unindex(value, u);
+        }
+        self.vertex_decorations.remove_element(u);
+        let graph = &self.graph;
+        self.edge_decorations.retain(|e| graph.get_vertices_from_edge(e).is_some());
+        Ok(())
+    }
+
+    /// Remove an edge and its value (see `GraphBuilder::try_remove_edge`)
+    pub fn try_remove_edge(&mut self, e: usize) -> Result<(), GraphError> {
+        let key = self.edge_key(e);
+        self.graph.try_remove_edge(e)?;
+        self.edge_decorations.remove_element(key);
+        Ok(())
+    }
+
+    /// Remove a vertex. Panics on error
+    pub fn remove_vertex(&mut self, u: usize) {
+        self.try_remove_vertex(u).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Remove an edge. Panics on error
+    pub fn remove_edge(&mut self, e: usize) {
+        self.try_remove_edge(e).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Compact the graph (see `GraphBuilder::compact`). The decorations follow the new ids.
+    pub fn compact(&mut self) -> Compaction {
+        let compaction = self.graph.compact();
+        self.vertex_decorations.remap(|u| compaction.vertex(u));
+        self.edge_decorations.remap(|e| compaction.edge(e));
+        if let Some(ref mut index) = self.value_index {
+            index.clear();
+            for (u, value) in self.vertex_decorations.indexed_values_iter() {
+                index.index(value, u);
+            }
+        }
+        compaction
+    }
+}
+
+impl<'a, G, V, E> GraphDecorator<'a, G, V, E>
+    where G: Graph<'a> + GraphBuilder<'a>,
+          V: 'a + PartialEq + Clone + Debug + Hash + Eq,
+          E: 'a + PartialEq + Clone + Debug
+{
+    /// Maintain an index vertex value -> vertices, for `vertex_by_value`
+    pub fn enable_value_index(&mut self) {
+        if self.value_index.is_some() {
+            return;
+        }
+        let mut index: HashMap<V, Vec<usize>> = HashMap::new();
+        for (u, value) in self.vertex_decorations.indexed_values_iter() {
+            index.index(value, u);
+        }
+        self.value_index = Some(Box::new(index));
+    }
+
+    /// The vertex with the given value, created if needed
+    pub fn vertex_or_add(&mut self, vertex_value: V) -> usize {
+        self.enable_value_index();
+        match self.vertex_by_value(&vertex_value) {
+            Some(u) => u,
+            None => self.add_vertex(vertex_value),
+        }
+    }
+
+    /// Add an edge between the vertices with the given values and return its id. The vertices
+    /// are created if needed, and the index of the values is enabled.
+    pub fn add_edge_by_value(&mut self, value1: V, value2: V, edge_value: E) -> usize {
+        let u = self.vertex_or_add(value1);
+        let v = self.vertex_or_add(value2);
+        self.add_edge(u, v, edge_value)
+    }
+}
+
+impl<'a, G, V, E> GraphDecorator<'a, G, V, E>
+    where G: Graph<'a>,
+          V: 'a + PartialEq + Clone + Debug,
+          E: 'a + PartialEq + Clone + Debug
+{
+    /// The value of an edge is stored under the smallest id of the edge and its twin.
+    fn edge_key(&self, e: usize) -> usize {
+        match self.graph.get_reversed_edge(e) {
+            Some(e2) if e2 < e => e2,
+            _ => e,
         }
     }
 }
@@ -146,7 +416,7 @@ impl<'a, G, V, E> Graph<'a> for GraphDecorator<'a, G, V, E>
         self.graph.edges_max()
     }
 
-    fn get_edges_from_vertices_iter(&self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
         self.graph.get_edges_from_vertices_iter(u, v)
     }
 
@@ -180,18 +450,41 @@ impl<'a, G, V, E> DecoratedGraph<'a, &'a V, &'a E> for GraphDecorator<'a, G, V,
     }
 
     fn edges_values_iter(&'a self, u: usize, v: usize) -> Self::EdgesValuesIterator {
-        Box::new(self.graph.get_edges_from_vertices_iter(u, v).map(move |e| (e, self.edge_decorations.get_value(e))))
+        Box::new(self.graph.get_edges_from_vertices_iter(u, v).map(move |e| (e, self.edge_decorations.get_value(self.edge_key(e)))))
     }
 }
 
 impl<'a, G, V, E> DirectedGraph<'a> for GraphDecorator<'a, G, V, E> where G: DirectedGraph<'a>,
                                                                           V: 'static + PartialEq + Clone + Debug,
                                                                           E: 'static + PartialEq + Clone + Debug
-{}
+{
+    type InNeighborsIterator = G::InNeighborsIterator;
+    type InEdgesIterator = G::InEdgesIterator;
+
+    fn in_neighbors_iter(&'a self, v: usize) -> Self::InNeighborsIterator {
+        self.graph.in_neighbors_iter(v)
+    }
+
+    fn in_edges_iter(&'a self, v: usize) -> Self::InEdgesIterator {
+        self.graph.in_edges_iter(v)
+    }
+
+    fn in_degree(&self, v: usize) -> usize {
+        self.graph.in_degree(v)
+    }
+
+    fn out_degree(&self, u: usize) -> usize {
+        self.graph.out_degree(u)
+    }
+}
 
 impl<'a, G, V, E> UndirectedGraph<'a> for GraphDecorator<'a, G, V, E> where G: UndirectedGraph<'a>, V: 'static + PartialEq + Clone + Debug,
                                                                             E: 'static + PartialEq + Clone + Debug
-{}
+{
+    fn degree(&self, u: usize) -> usize {
+        self.graph.degree(u)
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -223,4 +516,173 @@ mod test {
 // gh.output("gv_output/graph2.dot");
 //
     }
+
+    #[test]
+    fn test_owning_and_values() {
+        let mut dg: GraphDecorator<UndirectedSimpleGraphImpl, char, usize> =
+            GraphDecorator::owning(UndirectedSimpleGraphImpl::new(BasicGraph::new()));
+        let a = dg.add_vertex('A');
+        let b = dg.add_vertex('B');
+        let e = dg.add_edge(a, b, 3);
+        let e2 = dg.get_reversed_edge(e).unwrap();
+        assert_eq!(Some(&3), dg.edge_value(e2));
+
+        *dg.vertex_value_mut(a).unwrap() = 'Z';
+        *dg.edge_value_mut(e2).unwrap() += 1;
+        assert_eq!(Some(&'Z'), dg.vertex_value(a));
+        // the twins share their value
+        assert_eq!(Some(&4), dg.edge_value(e));
+        dg.set_edge_value(e, 7);
+        assert_eq!(vec![(e, Some(&7))], dg.edges_values_iter(b, a).collect::<Vec<_>>());
+        assert_eq!(2, dg.graph().vertices_size());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut dg = decorated_graph1(&mut g);
+        let e = dg.get_edges_from_vertices_iter(0, 1).next().unwrap();
+        dg.remove_edge(e);
+        assert_eq!(None, dg.edge_value(e));
+        assert_eq!(Err(GraphError::UnknownEdge(e)), dg.try_remove_edge(e));
+
+        // A-F, F-E, F-B disappear with F
+        let af = dg.get_edges_from_vertices_iter(0, 5).next().unwrap();
+        let fe = dg.get_edges_from_vertices_iter(5, 4).next().unwrap();
+        dg.remove_vertex(5);
+        assert_eq!(None, dg.vertex_value(5));
+        assert_eq!(None, dg.edge_value(af));
+        assert_eq!(None, dg.edge_value(fe));
+        assert_eq!(Some(&'E'), dg.vertex_value(4));
+        assert_eq!(1, dg.degree(0));
+        let ac = dg.get_edges_from_vertices_iter(0, 2).next().unwrap();
+        assert_eq!(Some(&3), dg.edge_value(ac));
+
+        // a new vertex reuses the id of F, without its value
+        let v = dg.add_vertex('N');
+        assert_eq!(5, v);
+        let e = dg.add_edge(v, 0, 8);
+        assert_eq!(Some(&8), dg.edge_value(e));
+    }
+
+    #[test]
+    fn test_set_value_then_add_vertex() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..3 {
+            g.create_vertex();
+        }
+        let mut dg: GraphDecorator<UndirectedSimpleGraphImpl, char, usize> = GraphDecorator::new(&mut g);
+        // 0 and 1 have no value
+        dg.set_vertex_value(2, 'C');
+        let d = dg.add_vertex('D');
+        dg.set_vertex_value(0, 'A');
+        let e = dg.add_vertex('E');
+        assert_eq!((3, 4), (d, e));
+        assert_eq!(vec![(0, Some(&'A')), (1, None), (2, Some(&'C')), (3, Some(&'D')), (4, Some(&'E'))],
+                   dg.vertices_values_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_values_not_hashed() {
+        let mut dg: GraphDecorator<UndirectedSimpleGraphImpl, f64, usize> =
+            GraphDecorator::owning(UndirectedSimpleGraphImpl::new(BasicGraph::new()));
+        let a = dg.add_vertex(0.5);
+        let b = dg.add_vertex(1.5);
+        let c = dg.add_vertex(2.5);
+        dg.add_edge(a, c, 1);
+        dg.set_vertex_value(b, 3.5);
+        *dg.vertex_value_mut(c).unwrap() += 1.0;
+        assert_eq!(Some(b), dg.vertex_by_value(&3.5));
+        dg.remove_vertex(a);
+        dg.compact();
+        assert_eq!(vec![(0, Some(&3.5)), (1, Some(&3.5))], dg.vertices_values_iter().collect::<Vec<_>>());
+        assert_eq!(Some(0), dg.vertex_by_value(&3.5));
+    }
+
+    #[test]
+    fn test_value_index() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let mut dg = decorated_graph1(&mut g);
+        // without index, the vertices are scanned
+        assert_eq!(Some(3), dg.vertex_by_value(&'D'));
+        dg.enable_value_index();
+        assert_eq!(Some(3), dg.vertex_by_value(&'D'));
+        assert_eq!(None, dg.vertex_by_value(&'Z'));
+
+        dg.set_vertex_value(3, 'Z');
+        assert_eq!(None, dg.vertex_by_value(&'D'));
+        assert_eq!(Some(3), dg.vertex_by_value(&'Z'));
+        *dg.vertex_value_mut(3).unwrap() = 'Y';
+        assert_eq!(None, dg.vertex_by_value(&'Z'));
+        assert_eq!(Some(3), dg.vertex_by_value(&'Y'));
+        dg.remove_vertex(3);
+        assert_eq!(None, dg.vertex_by_value(&'Y'));
+
+        dg.remove_vertex(0);
+        dg.compact();
+        assert_eq!(Some(0), dg.vertex_by_value(&'B'));
+        assert_eq!(Some(10), dg.vertex_by_value(&'M'));
+    }
+
+    #[test]
+    fn test_value_index_duplicates() {
+        let mut dg: GraphDecorator<UndirectedSimpleGraphImpl, char, usize> =
+            GraphDecorator::owning(UndirectedSimpleGraphImpl::new(BasicGraph::new()));
+        let a1 = dg.add_vertex('A');
+        let a2 = dg.add_vertex('A');
+        let a3 = dg.add_vertex('A');
+        dg.enable_value_index();
+        assert_eq!(Some(a1), dg.vertex_by_value(&'A'));
+        dg.remove_vertex(a1);
+        assert_eq!(Some(a2), dg.vertex_by_value(&'A'));
+        *dg.vertex_value_mut(a2).unwrap() = 'B';
+        assert_eq!(Some(a3), dg.vertex_by_value(&'A'));
+        dg.set_vertex_value(a3, 'C');
+        assert_eq!(None, dg.vertex_by_value(&'A'));
+
+        // no duplicate vertex is created while a vertex still has the value
+        dg.set_vertex_value(a3, 'B');
+        dg.set_vertex_value(a2, 'A');
+        dg.add_edge_by_value('A', 'B', 1);
+        assert_eq!(2, dg.vertices_size());
+        assert_eq!(Some(a3), dg.vertex_by_value(&'B'));
+    }
+
+    #[test]
+    fn test_add_edge_by_value() {
+        let mut dg: GraphDecorator<UndirectedSimpleGraphImpl, char, usize> =
+            GraphDecorator::owning(UndirectedSimpleGraphImpl::new(BasicGraph::new()));
+        let e = dg.add_edge_by_value('A', 'B', 3);
+        dg.add_edge_by_value('B', 'C', 2);
+        dg.add_edge_by_value('C', 'A', 1);
+        assert_eq!(3, dg.vertices_size());
+        let a = dg.vertex_by_value(&'A').unwrap();
+        let b = dg.vertex_by_value(&'B').unwrap();
+        assert_eq!(Some((b, a)), dg.get_vertices_from_edge(e));
+        assert_eq!(Some(&3), dg.edge_value(e));
+        assert_eq!(2, dg.degree(a));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..4 {
+            g.create_vertex();
+        }
+        g.remove_vertex(1);
+        let mut dg = GraphDecorator::new(&mut g);
+        dg.set_vertex_value(0, 'A');
+        dg.set_vertex_value(2, 'C');
+        dg.set_vertex_value(3, 'D');
+        dg.add_edge(0, 2, 5);
+        dg.add_edge(2, 3, 6);
+
+        let compaction = dg.compact();
+        assert_eq!(Some(1), compaction.vertex(2));
+        assert_eq!(vec![(0, Some(&'A')), (1, Some(&'C')), (2, Some(&'D'))],
+                   dg.vertices_values_iter().collect::<Vec<_>>());
+        assert_eq!(vec![Some(&5)], dg.edges_values_iter(0, 1).map(|(_, w)| w).collect::<Vec<_>>());
+        assert_eq!(vec![Some(&5)], dg.edges_values_iter(1, 0).map(|(_, w)| w).collect::<Vec<_>>());
+        assert_eq!(vec![Some(&6)], dg.edges_values_iter(1, 2).map(|(_, w)| w).collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,47 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::error::Error;
+use std::fmt;
+
+/// Why a `GraphBuilder` rejected an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphError {
+    /// the vertex does not exist, or was removed
+    UnknownVertex(usize),
+    /// the edge does not exist, or was removed
+    UnknownEdge(usize),
+    /// there is already an edge u->v and the graph has at most one
+    DuplicateEdge(usize, usize),
+    /// the graph does not accept an edge u->u
+    SelfLoop(usize),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphError::UnknownVertex(u) => write!(f, "unknown vertex {}", u),
+            GraphError::UnknownEdge(e) => write!(f, "unknown edge {}", e),
+            GraphError::DuplicateEdge(u, v) => write!(f, "duplicate edge {}->{}", u, v),
+            GraphError::SelfLoop(u) => write!(f, "self-loop on vertex {}", u),
+        }
+    }
+}
+
+impl Error for GraphError {}
@@ -19,9 +19,36 @@
 /// ***************************************************************************
 use graph::graph::Graph;
 
-pub trait DirectedGraph<'a>: Graph<'a> {}
-
-pub trait UndirectedGraph<'a>: Graph<'a> {}
+pub trait DirectedGraph<'a>: Graph<'a> {
+    type InNeighborsIterator: Iterator<Item = usize>;
+    type InEdgesIterator: Iterator<Item = (&'a usize, &'a usize)>;
+
+    /// return an Iterator on the vertices u such that there is an edge u->v
+    fn in_neighbors_iter(&'a self, v: usize) -> Self::InNeighborsIterator;
+
+    /// return an Iterator on the (u, e) such that e = u->v
+    fn in_edges_iter(&'a self, v: usize) -> Self::InEdgesIterator;
+
+    /// return the number of edges *->v
+    fn in_degree(&self, v: usize) -> usize;
+
+    /// return the number of edges u->*
+    fn out_degree(&self, u: usize) -> usize;
+}
+
+pub trait UndirectedGraph<'a>: Graph<'a> {
+    /// return the number of edges incident to u
+    fn degree(&self, u: usize) -> usize;
+
+    /// An undirected edge is stored as two twin edges u->v and v->u. Given one of them,
+    /// return the one that is yielded by `edges_iter` (the one with u < v)
+    fn main_edge(&self, e: usize) -> usize {
+        match (self.get_vertices_from_edge(e), self.get_reversed_edge(e)) {
+            (Some((u, v)), Some(e2)) if u > v => e2,
+            _ => e,
+        }
+    }
+}
 
 pub trait SimpleGraph<'a>: Graph<'a> {}
 
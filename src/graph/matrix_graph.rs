@@ -0,0 +1,780 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::collections::HashMap;
+use std::iter;
+use std::iter::Map;
+use std::option;
+
+use graph::basic_graph::BasicGraph;
+use graph::basic_graph::Compaction;
+use graph::basic_graph::SelfLoops;
+use graph::DecoratedGraph;
+use graph::DirectedGraph;
+use graph::Graph;
+use graph::GraphBuilder;
+use graph::GraphError;
+use graph::UndirectedGraph;
+use util::BitSet;
+use util::dense_vec_indices::DenseVecIndices;
+use util::dense_vec_indices::UsedIndicesIter;
+use util::iterator_util::EmptyOrOnceIter;
+use util::simple_edge_set::SimpleEdgeSet;
+
+/// A graph that knows the weight of the edge between two vertices in O(1).
+pub trait WeightMatrix {
+    /// None if there is no edge u->v, else the weight of the edge (0 if it has no weight)
+    fn edge_weight(&self, u: usize, v: usize) -> Option<usize>;
+}
+
+/// The ids of the arcs. A map (u, v) -> id costs O(E); a matrix of ids costs O(V^2), but is
+/// smaller than the map for very dense graphs.
+enum EdgeIds {
+    Map(HashMap<(usize, usize), usize>),
+    Matrix(Vec<usize>),
+}
+
+/// A square matrix of arcs. `adjacency` tells if there is an arc u->v, `edge_ids` gives its id.
+/// The matrices are stored row by row, with `capacity` columns; the capacity is doubled when a
+/// vertex does not fit.
+struct AdjacencyMatrix {
+    vertices: DenseVecIndices,
+    edges: DenseVecIndices,
+    capacity: usize,
+    adjacency: BitSet,
+    edge_ids: EdgeIds,
+    // vertex_ids[u] == u, to lend references to the vertices
+    vertex_ids: Vec<usize>,
+    edge_to_vertices: Vec<Option<(usize, usize)>>,
+    weights: Vec<Option<usize>>,
+    out_degree: Vec<usize>,
+    in_degree: Vec<usize>,
+    self_loops: SelfLoops,
+}
+
+impl AdjacencyMatrix {
+    /// An empty matrix. With `edge_id_matrix`, the ids of the arcs are stored in a matrix.
+    fn new(edge_id_matrix: bool) -> AdjacencyMatrix {
+        AdjacencyMatrix {
+            vertices: DenseVecIndices::new(),
+            edges: DenseVecIndices::new(),
+            capacity: 0,
+            adjacency: BitSet::new(0),
+            edge_ids: if edge_id_matrix { EdgeIds::Matrix(Vec::new()) } else { EdgeIds::Map(HashMap::new()) },
+            vertex_ids: Vec::new(),
+            edge_to_vertices: Vec::new(),
+            weights: Vec::new(),
+            out_degree: Vec::new(),
+            in_degree: Vec::new(),
+            self_loops: SelfLoops::Allow,
+        }
+    }
+
+    /// Copy the vertices and the arcs of a `BasicGraph`, with their ids
+    fn from_basic_graph(basic_graph: &BasicGraph<SimpleEdgeSet<usize, usize>>) -> AdjacencyMatrix {
+        let mut matrix = AdjacencyMatrix::new(false);
+        matrix.self_loops = basic_graph.self_loops();
+        for u in basic_graph.vertices_iter() {
+            matrix.vertices.use_index(u);
+        }
+        matrix.reserve(basic_graph.vertices_max());
+        for e in basic_graph.edges_iter() {
+            if let Some((u, v)) = basic_graph.get_vertices_from_edge(e) {
+                if matrix.arc(u, v).is_none() {
+                    matrix.edges.use_index(e);
+                    matrix.set_arc(u, v, e);
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Renumber the vertices and the arcs. The weights follow the arcs.
+    fn compact(&mut self) -> Compaction {
+        let compaction = Compaction::new(&self.vertices, &self.edges, |e| self.vertices_of(e));
+        let edge_id_matrix = match self.edge_ids {
+            EdgeIds::Map(_) => false,
+            EdgeIds::Matrix(_) => true,
+        };
+        let mut matrix = AdjacencyMatrix::new(edge_id_matrix);
+        matrix.self_loops = self.self_loops;
+        matrix.vertices = DenseVecIndices::new_dense(compaction.vertices_size());
+        matrix.edges = DenseVecIndices::new_dense(compaction.edges_size());
+        matrix.reserve(compaction.vertices_size());
+        for e in self.edges.used_indices_iter() {
+            if let (Some(f), Some((u, v))) = (compaction.edge(e), compaction.arc(self.vertices_of(e))) {
+                matrix.set_arc(u, v, f);
+                if let Some(&weight) = self.weight(e) {
+                    matrix.set_weight(f, weight);
+                }
+            }
+        }
+        *self = matrix;
+        compaction
+    }
+
+    fn reserve(&mut self, n: usize) {
+        if n <= self.capacity {
+            return;
+        }
+        let capacity = n.max(2 * self.capacity).max(4);
+        let mut adjacency = BitSet::new(capacity * capacity);
+        for u in 0..self.capacity {
+            for v in 0..self.capacity {
+                if self.adjacency.contains(u * self.capacity + v) {
+                    adjacency.insert(u * capacity + v);
+                }
+            }
+        }
+        if let EdgeIds::Matrix(ref mut ids) = self.edge_ids {
+            let mut edge_ids = vec![0; capacity * capacity];
+            for u in 0..self.capacity {
+                for v in 0..self.capacity {
+                    edge_ids[u * capacity + v] = ids[u * self.capacity + v];
+                }
+            }
+            *ids = edge_ids;
+        }
+        self.capacity = capacity;
+        self.adjacency = adjacency;
+        self.vertex_ids = (0..capacity).collect();
+        self.out_degree.resize(capacity, 0);
+        self.in_degree.resize(capacity, 0);
+    }
+
+    fn add_vertex(&mut self) -> usize {
+        let u = self.vertices.index_consume();
+        self.reserve(u + 1);
+        u
+    }
+
+    /// Remove a vertex and its edges
+    fn remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        if !self.vertices.index_is_used(u) {
+            return Err(GraphError::UnknownVertex(u));
+        }
+        for v in 0..self.capacity {
+            for &(x, y) in &[(u, v), (v, u)] {
+                if let Some(e) = self.arc(x, y) {
+                    self.remove_arc(e)?;
+                }
+            }
+        }
+        self.vertices.free_index(u);
+        Ok(())
+    }
+
+    /// Add an arc u->v and return its id
+    fn add_arc(&mut self, u: usize, v: usize) -> Result<usize, GraphError> {
+        for &w in &[u, v] {
+            if !self.vertices.index_is_used(w) {
+                return Err(GraphError::UnknownVertex(w));
+            }
+        }
+        if u == v && self.self_loops == SelfLoops::Forbid {
+            return Err(GraphError::SelfLoop(u));
+        }
+        if self.arc(u, v).is_some() {
+            return Err(GraphError::DuplicateEdge(u, v));
+        }
+        let e = self.edges.index_consume();
+        self.set_arc(u, v, e);
+        Ok(e)
+    }
+
+    fn set_arc(&mut self, u: usize, v: usize, e: usize) {
+        let cell = u * self.capacity + v;
+        self.adjacency.insert(cell);
+        match self.edge_ids {
+            EdgeIds::Map(ref mut ids) => {
+                ids.insert((u, v), e);
+            }
+            EdgeIds::Matrix(ref mut ids) => ids[cell] = e,
+        }
+        if self.edge_to_vertices.len() <= e {
+            self.edge_to_vertices.resize(e + 1, None);
+        }
+        self.edge_to_vertices[e] = Some((u, v));
+        self.out_degree[u] += 1;
+        self.in_degree[v] += 1;
+    }
+
+    fn remove_arc(&mut self, e: usize) -> Result<(), GraphError> {
+        let (u, v) = self.vertices_of(e).ok_or(GraphError::UnknownEdge(e))?;
+        self.adjacency.remove(u * self.capacity + v);
+        if let EdgeIds::Map(ref mut ids) = self.edge_ids {
+            ids.remove(&(u, v));
+        }
+        self.edges.free_index(e);
+        self.edge_to_vertices[e] = None;
+        if let Some(w) = self.weights.get_mut(e) {
+            *w = None;
+        }
+        self.out_degree[u] -= 1;
+        self.in_degree[v] -= 1;
+        Ok(())
+    }
+
+    fn arc(&self, u: usize, v: usize) -> Option<usize> {
+        let cell = u * self.capacity + v;
+        if u < self.capacity && v < self.capacity && self.adjacency.contains(cell) {
+            Some(*self.edge_id(u, v))
+        } else {
+            None
+        }
+    }
+
+    /// The id of the arc u->v, that must exist
+    fn edge_id(&self, u: usize, v: usize) -> &usize {
+        match self.edge_ids {
+            EdgeIds::Map(ref ids) => &ids[&(u, v)],
+            EdgeIds::Matrix(ref ids) => &ids[u * self.capacity + v],
+        }
+    }
+
+    fn vertices_of(&self, e: usize) -> Option<(usize, usize)> {
+        self.edge_to_vertices.get(e).cloned().unwrap_or(None)
+    }
+
+    fn set_weight(&mut self, e: usize, weight: usize) {
+        if self.weights.len() <= e {
+            self.weights.resize(e + 1, None);
+        }
+        self.weights[e] = Some(weight);
+    }
+
+    fn weight(&self, e: usize) -> Option<&usize> {
+        self.weights.get(e).and_then(|o_w| o_w.as_ref())
+    }
+
+    fn edge_weight(&self, u: usize, v: usize) -> Option<usize> {
+        self.arc(u, v).map(|e| self.weight(e).cloned().unwrap_or(0))
+    }
+
+    fn edges_from_vertices_iter(&self, u: usize, v: usize) -> EmptyOrOnceIter {
+        match self.arc(u, v) {
+            None => EmptyOrOnceIter::UEmpty(iter::empty()),
+            Some(e) => EmptyOrOnceIter::UOnce(iter::once(e)),
+        }
+    }
+
+    fn edges_values_iter(&self, u: usize, v: usize) -> option::IntoIter<(usize, Option<&usize>)> {
+        self.arc(u, v).map(|e| (e, self.weight(e))).into_iter()
+    }
+
+    fn line_iter(&self, u: usize, column: bool) -> MatrixLineIter<'_> {
+        MatrixLineIter {
+            matrix: self,
+            u,
+            v: 0,
+            column,
+        }
+    }
+}
+
+/// An iterator on the (vertex, edge) of a row (the arcs u->*) or of a column (the arcs *->u)
+/// of the matrix.
+pub struct MatrixLineIter<'a> {
+    matrix: &'a AdjacencyMatrix,
+    u: usize,
+    v: usize,
+    column: bool,
+}
+
+impl<'a> Iterator for MatrixLineIter<'a> {
+    type Item = (&'a usize, &'a usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = self.matrix;
+        if self.u >= m.capacity {
+            return None;
+        }
+        while self.v < m.vertices.max() {
+            let v = self.v;
+            self.v += 1;
+            let (x, y) = if self.column { (v, self.u) } else { (self.u, v) };
+            if m.adjacency.contains(x * m.capacity + y) {
+                return Some((&m.vertex_ids[v], m.edge_id(x, y)));
+            }
+        }
+        None
+    }
+}
+
+pub type MatrixNeighborsIter<'a> = Map<MatrixLineIter<'a>, fn((&usize, &usize)) -> usize>;
+
+/// A directed graph backed by an adjacency matrix, for dense graphs: O(1) edge lookup, O(V)
+/// neighbors iteration and a bitset of V^2 bits. The ids of the edges are in a map, or in a
+/// matrix (see `with_edge_id_matrix`). There is at most one edge u->v. An edge may have a
+/// weight.
+pub struct DirectedMatrixGraph {
+    matrix: AdjacencyMatrix,
+}
+
+impl DirectedMatrixGraph {
+    /// A graph with n vertices and no edge
+    pub fn with_vertices(n: usize) -> DirectedMatrixGraph {
+        Self::with_matrix(AdjacencyMatrix::new(false), n)
+    }
+
+    /// A graph with n vertices and no edge, that stores the ids of the edges in a V^2 matrix
+    /// rather than in a map: this is smaller for very dense graphs.
+    pub fn with_edge_id_matrix(n: usize) -> DirectedMatrixGraph {
+        Self::with_matrix(AdjacencyMatrix::new(true), n)
+    }
+
+    fn with_matrix(matrix: AdjacencyMatrix, n: usize) -> DirectedMatrixGraph {
+        let mut g = DirectedMatrixGraph { matrix };
+        g.matrix.reserve(n);
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        g
+    }
+
+    /// Add an edge u->v with a weight, and return its id
+    pub fn add_weighted_edge(&mut self, u: usize, v: usize, weight: usize) -> usize {
+        let e = self.add_edge(u, v);
+        self.matrix.set_weight(e, weight);
+        e
+    }
+
+    pub fn set_weight(&mut self, e: usize, weight: usize) {
+        self.matrix.set_weight(e, weight);
+    }
+
+    pub fn has_edge(&self, u: usize, v: usize) -> bool {
+        self.matrix.arc(u, v).is_some()
+    }
+}
+
+impl WeightMatrix for DirectedMatrixGraph {
+    fn edge_weight(&self, u: usize, v: usize) -> Option<usize> {
+        self.matrix.edge_weight(u, v)
+    }
+}
+
+impl<'a> GraphBuilder<'a> for DirectedMatrixGraph {
+    type ES = SimpleEdgeSet<usize, usize>;
+
+    fn new(basic_graph: BasicGraph<SimpleEdgeSet<usize, usize>>) -> DirectedMatrixGraph {
+        DirectedMatrixGraph {
+            matrix: AdjacencyMatrix::from_basic_graph(&basic_graph),
+        }
+    }
+
+    fn create_vertex(&mut self) -> usize {
+        self.matrix.add_vertex()
+    }
+
+    fn self_loops(&self) -> SelfLoops {
+        self.matrix.self_loops
+    }
+
+    fn try_remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        self.matrix.remove_vertex(u)
+    }
+
+    fn try_add_edge(&mut self, u: usize, v: usize) -> Result<usize, GraphError> {
+        self.matrix.add_arc(u, v)
+    }
+
+    fn try_remove_edge(&mut self, e: usize) -> Result<(), GraphError> {
+        self.matrix.remove_arc(e)
+    }
+
+    fn compact(&mut self) -> Compaction {
+        self.matrix.compact()
+    }
+}
+
+impl<'a> Graph<'a> for DirectedMatrixGraph {
+    type VerticesIterator = UsedIndicesIter<'a>;
+    type EdgesIterator = UsedIndicesIter<'a>;
+    type EdgesFromVerticesIterator = EmptyOrOnceIter;
+    type AdjacentVerticesIterator = MatrixNeighborsIter<'a>;
+    type AdjacentEdgesByVerticesIterator = MatrixLineIter<'a>;
+
+    fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
+        self.matrix.vertices_of(e)
+    }
+
+    fn get_reversed_edge(&self, _: usize) -> Option<usize> {
+        None
+    }
+
+    fn vertices_size(&self) -> usize {
+        self.matrix.vertices.size()
+    }
+
+    fn vertices_max(&self) -> usize {
+        self.matrix.vertices.max()
+    }
+
+    fn edges_size(&self) -> usize {
+        self.matrix.edges.size()
+    }
+
+    fn edges_max(&self) -> usize {
+        self.matrix.edges.max()
+    }
+
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+        self.matrix.edges_from_vertices_iter(u, v)
+    }
+
+    fn vertices_iter(&'a self) -> Self::VerticesIterator {
+        self.matrix.vertices.used_indices_iter()
+    }
+
+    fn edges_iter(&'a self) -> Self::EdgesIterator {
+        self.matrix.edges.used_indices_iter()
+    }
+
+    fn adjacent_vertices_iter(&'a self, u: usize) -> Self::AdjacentVerticesIterator {
+        self.matrix.line_iter(u, false).map(|(&v, _)| v)
+    }
+
+    fn adjacent_edges_by_vertex_iter(&'a self, u: usize) -> Self::AdjacentEdgesByVerticesIterator {
+        self.matrix.line_iter(u, false)
+    }
+}
+
+impl<'a> DecoratedGraph<'a, usize, &'a usize> for DirectedMatrixGraph {
+    type VerticesValuesIterator = Map<<DirectedMatrixGraph as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+    type EdgesValuesIterator = option::IntoIter<(usize, Option<&'a usize>)>;
+
+    fn vertices_values_iter(&'a self) -> Self::VerticesValuesIterator {
+        self.vertices_iter().map(|i| (i, Some(i)))
+    }
+
+    fn edges_values_iter(&'a self, u: usize, v: usize) -> Self::EdgesValuesIterator {
+        self.matrix.edges_values_iter(u, v)
+    }
+}
+
+impl<'a> DirectedGraph<'a> for DirectedMatrixGraph {
+    type InNeighborsIterator = MatrixNeighborsIter<'a>;
+    type InEdgesIterator = MatrixLineIter<'a>;
+
+    fn in_neighbors_iter(&'a self, v: usize) -> Self::InNeighborsIterator {
+        self.matrix.line_iter(v, true).map(|(&u, _)| u)
+    }
+
+    fn in_edges_iter(&'a self, v: usize) -> Self::InEdgesIterator {
+        self.matrix.line_iter(v, true)
+    }
+
+    fn in_degree(&self, v: usize) -> usize {
+        self.matrix.in_degree.get(v).cloned().unwrap_or(0)
+    }
+
+    fn out_degree(&self, u: usize) -> usize {
+        self.matrix.out_degree.get(u).cloned().unwrap_or(0)
+    }
+}
+
+/// An undirected graph backed by a symmetric adjacency matrix. As in
+/// `UndirectedSimpleGraphImpl`, an undirected edge is a couple of twin edges u->v and v->u.
+pub struct UndirectedMatrixGraph {
+    matrix: AdjacencyMatrix,
+}
+
+impl UndirectedMatrixGraph {
+    /// A graph with n vertices and no edge
+    pub fn with_vertices(n: usize) -> UndirectedMatrixGraph {
+        Self::with_matrix(AdjacencyMatrix::new(false), n)
+    }
+
+    /// A graph with n vertices and no edge, that stores the ids of the edges in a V^2 matrix
+    /// rather than in a map: this is smaller for very dense graphs.
+    pub fn with_edge_id_matrix(n: usize) -> UndirectedMatrixGraph {
+        Self::with_matrix(AdjacencyMatrix::new(true), n)
+    }
+
+    fn with_matrix(matrix: AdjacencyMatrix, n: usize) -> UndirectedMatrixGraph {
+        let mut g = UndirectedMatrixGraph { matrix };
+        g.matrix.reserve(n);
+        for _ in 0..n {
+            g.create_vertex();
+        }
+        g
+    }
+
+    /// Add an edge u-v with a weight, and return its id
+    pub fn add_weighted_edge(&mut self, u: usize, v: usize, weight: usize) -> usize {
+        let e = self.add_edge(u, v);
+        self.set_weight(e, weight);
+        e
+    }
+
+    /// Set the weight of an edge and of its twin
+    pub fn set_weight(&mut self, e: usize, weight: usize) {
+        self.matrix.set_weight(e, weight);
+        if let Some(e2) = self.get_reversed_edge(e) {
+            self.matrix.set_weight(e2, weight);
+        }
+    }
+
+    pub fn has_edge(&self, u: usize, v: usize) -> bool {
+        self.matrix.arc(u, v).is_some()
+    }
+
+    fn is_main_edge(&self, e: usize) -> bool {
+        match self.matrix.vertices_of(e) {
+            None => false,
+            Some((u, v)) => u <= v,
+        }
+    }
+}
+
+impl WeightMatrix for UndirectedMatrixGraph {
+    fn edge_weight(&self, u: usize, v: usize) -> Option<usize> {
+        self.matrix.edge_weight(u, v)
+    }
+}
+
+impl<'a> GraphBuilder<'a> for UndirectedMatrixGraph {
+    type ES = SimpleEdgeSet<usize, usize>;
+
+    fn new(basic_graph: BasicGraph<SimpleEdgeSet<usize, usize>>) -> UndirectedMatrixGraph {
+        UndirectedMatrixGraph {
+            matrix: AdjacencyMatrix::from_basic_graph(&basic_graph),
+        }
+    }
+
+    fn create_vertex(&mut self) -> usize {
+        self.matrix.add_vertex()
+    }
+
+    fn self_loops(&self) -> SelfLoops {
+        self.matrix.self_loops
+    }
+
+    fn try_remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        self.matrix.remove_vertex(u)
+    }
+
+    /// A loop u-u is a single arc u->u, that is its own twin.
+    fn try_add_edge(&mut self, u: usize, v: usize) -> Result<usize, GraphError> {
+        if u == v {
+            return self.matrix.add_arc(u, u);
+        }
+        let e1 = self.matrix.add_arc(v, u)?;
+        self.matrix.add_arc(u, v)?;
+        Ok(e1)
+    }
+
+    fn try_remove_edge(&mut self, e: usize) -> Result<(), GraphError> {
+        let e2 = self.get_reversed_edge(e).ok_or(GraphError::UnknownEdge(e))?;
+        if e2 != e {
+            self.matrix.remove_arc(e2)?;
+        }
+        self.matrix.remove_arc(e)
+    }
+
+    fn compact(&mut self) -> Compaction {
+        self.matrix.compact()
+    }
+}
+
+impl<'a> Graph<'a> for UndirectedMatrixGraph {
+    type VerticesIterator = UsedIndicesIter<'a>;
+    type EdgesIterator = Box<dyn Iterator<Item=usize> + 'a>; // as in UndirectedSimpleGraphImpl
+    type EdgesFromVerticesIterator = EmptyOrOnceIter;
+    type AdjacentVerticesIterator = MatrixNeighborsIter<'a>;
+    type AdjacentEdgesByVerticesIterator = MatrixLineIter<'a>;
+
+    fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
+        self.matrix.vertices_of(e)
+    }
+
+    fn get_reversed_edge(&self, e: usize) -> Option<usize> {
+        self.matrix.vertices_of(e).and_then(|(u, v)| self.matrix.arc(v, u))
+    }
+
+    fn vertices_size(&self) -> usize {
+        self.matrix.vertices.size()
+    }
+
+    fn vertices_max(&self) -> usize {
+        self.matrix.vertices.max()
+    }
+
+    fn edges_size(&self) -> usize {
+        self.matrix.edges.size()
+    }
+
+    fn edges_max(&self) -> usize {
+        self.matrix.edges.max()
+    }
+
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+        self.matrix.edges_from_vertices_iter(u, v)
+    }
+
+    fn vertices_iter(&'a self) -> Self::VerticesIterator {
+        self.matrix.vertices.used_indices_iter()
+    }
+
+    fn edges_iter(&'a self) -> Self::EdgesIterator {
+        Box::new(self.matrix.edges.used_indices_iter().filter(move |&e| self.is_main_edge(e)))
+    }
+
+    fn adjacent_vertices_iter(&'a self, u: usize) -> Self::AdjacentVerticesIterator {
+        self.matrix.line_iter(u, false).map(|(&v, _)| v)
+    }
+
+    fn adjacent_edges_by_vertex_iter(&'a self, u: usize) -> Self::AdjacentEdgesByVerticesIterator {
+        self.matrix.line_iter(u, false)
+    }
+}
+
+impl<'a> DecoratedGraph<'a, usize, &'a usize> for UndirectedMatrixGraph {
+    type VerticesValuesIterator = Map<<UndirectedMatrixGraph as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+    type EdgesValuesIterator = option::IntoIter<(usize, Option<&'a usize>)>;
+
+    fn vertices_values_iter(&'a self) -> Self::VerticesValuesIterator {
+        self.vertices_iter().map(|i| (i, Some(i)))
+    }
+
+    fn edges_values_iter(&'a self, u: usize, v: usize) -> Self::EdgesValuesIterator {
+        self.matrix.edges_values_iter(u, v)
+    }
+}
+
+impl<'a> UndirectedGraph<'a> for UndirectedMatrixGraph {
+    /// A loop counts twice.
+    fn degree(&self, u: usize) -> usize {
+        let loops = self.matrix.arc(u, u).map_or(0, |_| 1);
+        self.matrix.out_degree.get(u).cloned().unwrap_or(0) + loops
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::examples::graph1;
+    use graph::DirectedSimpleGraphImpl;
+
+    use super::*;
+
+    #[test]
+    fn test_directed() {
+        let g = graph1::<DirectedMatrixGraph>();
+        let h = graph1::<DirectedSimpleGraphImpl>();
+        assert_eq!(h.edges_size(), g.edges_size());
+        for u in h.vertices_iter() {
+            let mut neighbors: Vec<usize> = h.adjacent_vertices_iter(u).collect();
+            neighbors.sort();
+            assert_eq!(neighbors, g.adjacent_vertices_iter(u).collect::<Vec<usize>>());
+            assert_eq!(h.in_degree(u), g.in_degree(u));
+        }
+        assert!(g.has_edge(0, 1));
+        assert!(!g.has_edge(1, 0));
+        assert_eq!(vec![0, 4], g.in_neighbors_iter(5).collect::<Vec<usize>>());
+        let e = g.get_edges_from_vertices_iter(4, 5).next().expect("an edge");
+        assert_eq!(Some((4, 5)), g.get_vertices_from_edge(e));
+    }
+
+    #[test]
+    fn test_grow_and_remove() {
+        let mut g = DirectedMatrixGraph::with_vertices(3);
+        g.add_weighted_edge(0, 2, 7);
+        assert_eq!(Err(GraphError::DuplicateEdge(0, 2)), g.try_add_edge(0, 2));
+        for _ in 0..10 {
+            g.create_vertex();
+        }
+        g.add_edge(12, 0);
+        assert_eq!(Some(7), g.edge_weight(0, 2));
+        assert_eq!(Some(0), g.edge_weight(12, 0));
+        assert_eq!(None, g.edge_weight(2, 0));
+        g.remove_vertex(0);
+        assert_eq!(0, g.edges_size());
+        assert_eq!(0, g.in_degree(2));
+        assert_eq!(12, g.vertices_size());
+    }
+
+    #[test]
+    fn test_edge_id_matrix() {
+        let mut g = DirectedMatrixGraph::with_edge_id_matrix(3);
+        let e02 = g.add_weighted_edge(0, 2, 7);
+        let e10 = g.add_edge(1, 0);
+        // the ids follow the arcs when the matrix grows
+        for _ in 0..10 {
+            g.create_vertex();
+        }
+        let e12 = g.add_edge(12, 0);
+        assert_eq!(Some(7), g.edge_weight(0, 2));
+        assert_eq!(vec![e02], g.get_edges_from_vertices_iter(0, 2).collect::<Vec<usize>>());
+        assert_eq!(vec![(&1, &e10), (&12, &e12)], g.in_edges_iter(0).collect::<Vec<(&usize, &usize)>>());
+        g.remove_edge(e10);
+        assert!(!g.has_edge(1, 0));
+
+        let mut g = UndirectedMatrixGraph::with_edge_id_matrix(3);
+        let e = g.add_edge(0, 1);
+        let e2 = g.get_reversed_edge(e).expect("a twin");
+        assert_eq!(Some((0, 1)), g.get_vertices_from_edge(e2));
+        assert_eq!(vec![(&1, &e2)], g.adjacent_edges_by_vertex_iter(0).collect::<Vec<(&usize, &usize)>>());
+    }
+
+    #[test]
+    fn test_undirected() {
+        let mut g = UndirectedMatrixGraph::with_vertices(4);
+        let e = g.add_weighted_edge(0, 1, 3);
+        g.add_edge(1, 2);
+        g.add_edge(3, 1);
+        assert_eq!(3, g.degree(1));
+        assert_eq!(3, g.edges_iter().count());
+        assert_eq!(Some(3), g.edge_weight(1, 0));
+        let e2 = g.get_reversed_edge(e).expect("a twin");
+        assert_eq!(Some((0, 1)), g.get_vertices_from_edge(e2));
+        g.remove_edge(e2);
+        assert!(!g.has_edge(0, 1));
+        assert!(!g.has_edge(1, 0));
+        assert_eq!(2, g.degree(1));
+        let l = g.add_edge(1, 1);
+        assert_eq!(Some(l), g.get_reversed_edge(l));
+        assert_eq!(4, g.degree(1));
+        g.remove_edge(l);
+        assert_eq!(2, g.degree(1));
+
+        let mut g: UndirectedMatrixGraph = GraphBuilder::new(BasicGraph::with_self_loops(SelfLoops::Forbid));
+        let u = g.create_vertex();
+        assert_eq!(Err(GraphError::SelfLoop(u)), g.try_add_edge(u, u));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut g = DirectedMatrixGraph::with_vertices(6);
+        g.add_weighted_edge(0, 1, 4);
+        g.add_weighted_edge(5, 3, 2);
+        g.add_edge(3, 5);
+        g.remove_vertex(1);
+        g.remove_vertex(2);
+        let compaction = g.compact();
+        assert_eq!(4, g.vertices_max());
+        assert_eq!(2, g.edges_max());
+        assert_eq!(Some(3), compaction.vertex(5));
+        assert_eq!(Some(2), g.edge_weight(3, 1));
+        assert_eq!(Some(0), g.edge_weight(1, 3));
+        assert_eq!(None, g.edge_weight(0, 1));
+        assert_eq!(1, g.in_degree(1));
+        assert_eq!(vec![1], g.adjacent_vertices_iter(3).collect::<Vec<usize>>());
+    }
+}
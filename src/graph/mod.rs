@@ -26,12 +26,29 @@ pub mod directed_simple_graph;
 mod decorated_graph;
 pub mod graph_decorator;
 pub mod graph_builder;
+pub mod graph_error;
+pub mod reversed_graph;
+pub mod csr_graph;
+pub mod matrix_graph;
 
 pub use self::graph::Graph;
 pub use self::graph_builder::GraphBuilder;
+pub use self::graph_error::GraphError;
+pub use self::basic_graph::VertexHandle;
+pub use self::basic_graph::EdgeHandle;
+pub use self::basic_graph::Compaction;
+pub use self::basic_graph::SelfLoops;
 pub use self::undirected_simple_graph::UndirectedSimpleGraphImpl;
+pub use self::undirected_simple_graph::UndirectedVecGraphImpl;
 pub use self::directed_simple_graph::DirectedSimpleGraphImpl;
+pub use self::directed_simple_graph::DirectedVecGraphImpl;
 pub use self::decorated_graph::DecoratedGraph;
 pub use self::graph_decorator::GraphDecorator;
 pub use self::graphs::DirectedGraph;
 pub use self::graphs::UndirectedGraph;
+pub use self::reversed_graph::ReversedGraph;
+pub use self::csr_graph::DirectedCsrGraph;
+pub use self::csr_graph::UndirectedCsrGraph;
+pub use self::matrix_graph::DirectedMatrixGraph;
+pub use self::matrix_graph::UndirectedMatrixGraph;
+pub use self::matrix_graph::WeightMatrix;
@@ -0,0 +1,136 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use graph::DirectedGraph;
+use graph::Graph;
+
+/// A view of a directed graph where every edge u->v is seen as v->u. The edges keep their
+/// indices. Useful to browse a graph backward (e.g. post-dominators).
+pub struct ReversedGraph<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    graph: &'a G,
+}
+
+impl<'a, G> ReversedGraph<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    pub fn new(graph: &'a G) -> ReversedGraph<'a, G> {
+        ReversedGraph { graph }
+    }
+}
+
+impl<'a, G> Graph<'a> for ReversedGraph<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    type VerticesIterator = G::VerticesIterator;
+    type EdgesIterator = G::EdgesIterator;
+    type EdgesFromVerticesIterator = G::EdgesFromVerticesIterator;
+    type AdjacentVerticesIterator = G::InNeighborsIterator;
+    type AdjacentEdgesByVerticesIterator = G::InEdgesIterator;
+
+    fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
+        self.graph.get_vertices_from_edge(e).map(|(u, v)| (v, u))
+    }
+
+    fn get_reversed_edge(&self, e: usize) -> Option<usize> {
+        self.graph.get_reversed_edge(e)
+    }
+
+    fn vertices_size(&self) -> usize {
+        self.graph.vertices_size()
+    }
+
+    fn vertices_max(&self) -> usize {
+        self.graph.vertices_max()
+    }
+
+    fn edges_size(&self) -> usize {
+        self.graph.edges_size()
+    }
+
+    fn edges_max(&self) -> usize {
+        self.graph.edges_max()
+    }
+
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+        self.graph.get_edges_from_vertices_iter(v, u)
+    }
+
+    fn vertices_iter(&'a self) -> Self::VerticesIterator {
+        self.graph.vertices_iter()
+    }
+
+    fn edges_iter(&'a self) -> Self::EdgesIterator {
+        self.graph.edges_iter()
+    }
+
+    fn adjacent_vertices_iter(&'a self, u: usize) -> Self::AdjacentVerticesIterator {
+        self.graph.in_neighbors_iter(u)
+    }
+
+    fn adjacent_edges_by_vertex_iter(&'a self, u: usize) -> Self::AdjacentEdgesByVerticesIterator {
+        self.graph.in_edges_iter(u)
+    }
+}
+
+impl<'a, G> DirectedGraph<'a> for ReversedGraph<'a, G>
+    where G: 'a + DirectedGraph<'a>
+{
+    type InNeighborsIterator = G::AdjacentVerticesIterator;
+    type InEdgesIterator = G::AdjacentEdgesByVerticesIterator;
+
+    fn in_neighbors_iter(&'a self, v: usize) -> Self::InNeighborsIterator {
+        self.graph.adjacent_vertices_iter(v)
+    }
+
+    fn in_edges_iter(&'a self, v: usize) -> Self::InEdgesIterator {
+        self.graph.adjacent_edges_by_vertex_iter(v)
+    }
+
+    fn in_degree(&self, v: usize) -> usize {
+        self.graph.out_degree(v)
+    }
+
+    fn out_degree(&self, u: usize) -> usize {
+        self.graph.in_degree(u)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph::DirectedSimpleGraphImpl;
+    use graph::examples::graph1;
+
+    use super::*;
+
+    #[test]
+    fn test_reversed() {
+        let g = graph1::<DirectedSimpleGraphImpl>();
+        let r = ReversedGraph::new(&g);
+        let mut predecessors: Vec<usize> = r.adjacent_vertices_iter(5).collect();
+        predecessors.sort();
+        assert_eq!(vec![0, 4], predecessors);
+        assert_eq!(vec![3], r.adjacent_vertices_iter(4).collect::<Vec<usize>>());
+        let e = r.get_edges_from_vertices_iter(1, 0).next().expect("an edge");
+        assert_eq!(Some((1, 0)), r.get_vertices_from_edge(e));
+        assert_eq!(g.edges_size(), r.edges_size());
+        assert_eq!((2, 1), (r.out_degree(5), r.in_degree(5)));
+    }
+}
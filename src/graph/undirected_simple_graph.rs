@@ -17,29 +17,48 @@
 /// You should have received a copy of the GNU General Public License
 /// along with this program.  If not, see <http://www.gnu.org/licenses/>.
 /// ***************************************************************************
-use std::collections::hash_map;
 use std::iter::Map;
 use std::iter;
+use std::mem;
 
+use util::edge_set::EdgeSet;
 use util::simple_edge_set::SimpleEdgeSet;
+use util::vec_edge_set::VecEdgeSet;
 use util::iterator_util::EmptyOrOnceIter;
 use util::dense_vec_indices::UsedIndicesIter;
 use graph::decorated_graph::DecoratedGraph;
 use graph::graphs::UndirectedGraph;
 use graph::graph::Graph;
 use graph::graph_builder::GraphBuilder;
+use graph::graph_error::GraphError;
 use graph::basic_graph::BasicGraph;
+use graph::basic_graph::Compaction;
+use graph::basic_graph::SelfLoops;
+use graph::basic_graph::EdgeHandle;
+use graph::basic_graph::VertexHandle;
 
-pub struct UndirectedSimpleGraphImpl {
-    basic_graph: BasicGraph<SimpleEdgeSet<usize, usize>>,
+/// An undirected graph with at most one edge u-v, stored as two twin edges u->v and v->u. The
+/// edge set `ES` stores the adjacency.
+pub struct UndirectedGraphImpl<ES>
+    where ES: EdgeSet<usize, usize, S = usize>
+{
+    basic_graph: BasicGraph<ES>,
     reversed: Vec<usize>,
 }
 
-impl<'a> GraphBuilder<'a> for UndirectedSimpleGraphImpl {
-    type ES = SimpleEdgeSet<usize, usize>;
+/// The adjacency is a map: the neighbors are iterated in an arbitrary order.
+pub type UndirectedSimpleGraphImpl = UndirectedGraphImpl<SimpleEdgeSet<usize, usize>>;
 
-    fn new(basic_graph: BasicGraph<SimpleEdgeSet<usize, usize>>) -> UndirectedSimpleGraphImpl {
-        UndirectedSimpleGraphImpl {
+/// The adjacency is a Vec per vertex: the neighbors are iterated in insertion order.
+pub type UndirectedVecGraphImpl = UndirectedGraphImpl<VecEdgeSet<usize, usize>>;
+
+impl<'a, ES> GraphBuilder<'a> for UndirectedGraphImpl<ES>
+    where ES: EdgeSet<usize, usize, S = usize>
+{
+    type ES = ES;
+
+    fn new(basic_graph: BasicGraph<ES>) -> UndirectedGraphImpl<ES> {
+        UndirectedGraphImpl {
             reversed: Vec::with_capacity(basic_graph.edges_max()),
             basic_graph,
         }
@@ -49,31 +68,67 @@ impl<'a> GraphBuilder<'a> for UndirectedSimpleGraphImpl {
         self.basic_graph.add_vertex()
     }
 
-    fn remove_vertex(&mut self, u: usize) {
-        self.basic_graph.remove_vertex(u);
+    fn self_loops(&self) -> SelfLoops {
+        self.basic_graph.self_loops()
+    }
+
+    fn try_remove_vertex(&mut self, u: usize) -> Result<(), GraphError> {
+        self.basic_graph.remove_vertex(u)
     }
 
-    fn add_edge(&mut self, u: usize, v: usize) -> usize {
-        let e1 = self.basic_graph.add_edge(v, u);
-        let e2 = self.basic_graph.add_edge(u, v);
-        self.reversed.insert(e1, e2);
-        self.reversed.insert(e2, e1);
-        e1
+    /// A loop u-u is a single edge u->u, that is its own twin.
+    fn try_add_edge(&mut self, u: usize, v: usize) -> Result<usize, GraphError> {
+        if u == v {
+            let e = self.basic_graph.add_edge(u, u)?;
+            self.set_twins(e, e);
+            return Ok(e);
+        }
+        // the twin v->u is added first: report the duplicate edge as the caller's u-v
+        let e1 = self.basic_graph.add_edge(v, u).map_err(|err| match err {
+            GraphError::DuplicateEdge(_, _) => GraphError::DuplicateEdge(u, v),
+            err => err,
+        })?;
+        let e2 = match self.basic_graph.add_edge(u, v) {
+            Ok(e2) => e2,
+            Err(err) => {
+                self.basic_graph.remove_edge(e1)?;
+                return Err(err);
+            }
+        };
+        self.set_twins(e1, e2);
+        Ok(e1)
     }
 
-    fn remove_edge(&mut self, e: usize) {
+    fn try_remove_edge(&mut self, e: usize) -> Result<(), GraphError> {
+        self.basic_graph.get_vertices_from_edge(e).ok_or(GraphError::UnknownEdge(e))?;
         let e2 = self.reversed[e];
-        self.basic_graph.remove_edge(e);
-        self.basic_graph.remove_edge(e2);
+        self.basic_graph.remove_edge(e)?;
+        if e2 != e {
+            self.basic_graph.remove_edge(e2)?;
+        }
+        Ok(())
+    }
+
+    fn compact(&mut self) -> Compaction {
+        let compaction = self.basic_graph.compact();
+        let reversed = mem::take(&mut self.reversed);
+        for (e1, e2) in reversed.into_iter().enumerate() {
+            if let (Some(f1), Some(f2)) = (compaction.edge(e1), compaction.edge(e2)) {
+                self.set_twins(f1, f2);
+            }
+        }
+        compaction
     }
 }
 
-impl<'a> Graph<'a> for UndirectedSimpleGraphImpl {
+impl<'a, ES> Graph<'a> for UndirectedGraphImpl<ES>
+    where ES: 'a + EdgeSet<usize, usize, S = usize>
+{
     type VerticesIterator = UsedIndicesIter<'a>;
     type EdgesIterator = Box<dyn Iterator<Item=usize> + 'a>; // can't avoid box here
     type EdgesFromVerticesIterator = EmptyOrOnceIter;
-    type AdjacentVerticesIterator = Map<hash_map::Iter<'a, usize, usize>, fn((&usize, &usize)) -> usize>;
-    type AdjacentEdgesByVerticesIterator = hash_map::Iter<'a, usize, usize>;
+    type AdjacentVerticesIterator = Map<ES::EdgesByToIter<'a>, fn((&usize, &usize)) -> usize>;
+    type AdjacentEdgesByVerticesIterator = ES::EdgesByToIter<'a>;
 
     fn get_vertices_from_edge(&self, e: usize) -> Option<(usize, usize)> {
         self.basic_graph.get_vertices_from_edge(e)
@@ -102,7 +157,7 @@ impl<'a> Graph<'a> for UndirectedSimpleGraphImpl {
         self.basic_graph.edges_max()
     }
 
-    fn get_edges_from_vertices_iter(&self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
+    fn get_edges_from_vertices_iter(&'a self, u: usize, v: usize) -> Self::EdgesFromVerticesIterator {
         match self.basic_graph.get_edges_from_vertices(u, v) {
             None => {
                 match self.basic_graph.get_edges_from_vertices(v, u) {
@@ -133,9 +188,11 @@ impl<'a> Graph<'a> for UndirectedSimpleGraphImpl {
     }
 }
 
-impl<'a> DecoratedGraph<'a, usize, usize> for UndirectedSimpleGraphImpl {
-    type VerticesValuesIterator = Map<<UndirectedSimpleGraphImpl as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
-    type EdgesValuesIterator = Map<<UndirectedSimpleGraphImpl as Graph<'a>>::EdgesFromVerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+impl<'a, ES> DecoratedGraph<'a, usize, usize> for UndirectedGraphImpl<ES>
+    where ES: 'a + EdgeSet<usize, usize, S = usize>
+{
+    type VerticesValuesIterator = Map<<Self as Graph<'a>>::VerticesIterator, fn(usize) -> (usize, Option<usize>)>;
+    type EdgesValuesIterator = Map<<Self as Graph<'a>>::EdgesFromVerticesIterator, fn(usize) -> (usize, Option<usize>)>;
 
     fn vertices_values_iter(&'a self) -> Self::VerticesValuesIterator {
         self.vertices_iter().map(|i| (i, Some(i)))
@@ -146,15 +203,52 @@ impl<'a> DecoratedGraph<'a, usize, usize> for UndirectedSimpleGraphImpl {
     }
 }
 
-impl<'a> UndirectedGraph<'a> for UndirectedSimpleGraphImpl {}
+impl<'a, ES> UndirectedGraph<'a> for UndirectedGraphImpl<ES>
+    where ES: 'a + EdgeSet<usize, usize, S = usize>
+{
+    /// A loop counts twice.
+    fn degree(&self, u: usize) -> usize {
+        let loops = self.basic_graph.get_edges_from_vertices(u, u).map_or(0, |_| 1);
+        self.basic_graph.out_degree(u) + loops
+    }
+}
+
+impl<ES> UndirectedGraphImpl<ES>
+    where ES: EdgeSet<usize, usize, S = usize>
+{
+    fn set_twins(&mut self, e1: usize, e2: usize) {
+        let n = e1.max(e2) + 1;
+        if self.reversed.len() < n {
+            self.reversed.resize(n, 0);
+        }
+        self.reversed[e1] = e2;
+        self.reversed[e2] = e1;
+    }
 
-impl<'a> UndirectedSimpleGraphImpl {
     fn is_main_edge(&self, e: usize) -> bool {
         match self.basic_graph.get_vertices_from_edge(e) {
             None => false,
-            Some((u, v)) => u < v
+            Some((u, v)) => u <= v
         }
     }
+
+    /// A handle on the vertex `v` that detects a later removal of `v`.
+    pub fn vertex_handle(&self, v: usize) -> Option<VertexHandle> {
+        self.basic_graph.vertex_handle(v)
+    }
+
+    pub fn vertex_from_handle(&self, h: VertexHandle) -> Option<usize> {
+        self.basic_graph.vertex_from_handle(h)
+    }
+
+    /// A handle on the edge `e` that detects a later removal of `e`.
+    pub fn edge_handle(&self, e: usize) -> Option<EdgeHandle> {
+        self.basic_graph.edge_handle(e)
+    }
+
+    pub fn edge_from_handle(&self, h: EdgeHandle) -> Option<usize> {
+        self.basic_graph.edge_from_handle(h)
+    }
 }
 
 #[cfg(test)]
@@ -199,5 +293,80 @@ mod test {
         g.add_edge(3, 4);
         g.add_edge(3, 5);
         g.add_edge(4, 5);
+
+        assert_eq!(4, g.degree(0));
+        assert_eq!(2, g.degree(4));
+        assert_eq!(0, g.degree(12));
+    }
+
+    #[test]
+    fn test_try_add_edge() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let a = g.create_vertex();
+        let b = g.create_vertex();
+        let e = g.add_edge(a, b);
+        assert_eq!(Err(GraphError::DuplicateEdge(a, b)), g.try_add_edge(a, b));
+        assert_eq!(Err(GraphError::DuplicateEdge(b, a)), g.try_add_edge(b, a));
+        assert_eq!(2, g.edges_max());
+        assert_eq!(1, g.degree(a));
+        assert_eq!(Ok(()), g.try_remove_edge(e));
+        assert_eq!(Err(GraphError::UnknownEdge(e)), g.try_remove_edge(e));
+        assert_eq!(0, g.degree(b));
+    }
+
+    #[test]
+    fn test_self_loops() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::with_self_loops(SelfLoops::Forbid));
+        let a = g.create_vertex();
+        assert_eq!(Err(GraphError::SelfLoop(a)), g.try_add_edge(a, a));
+
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        let a = g.create_vertex();
+        let b = g.create_vertex();
+        g.add_edge(a, b);
+        let e = g.add_edge(b, b);
+        assert_eq!(Some(e), g.get_reversed_edge(e));
+        assert_eq!(e, g.main_edge(e));
+        assert_eq!(3, g.degree(b));
+        assert_eq!(vec![e], g.get_edges_from_vertices_iter(b, b).collect::<Vec<usize>>());
+        assert_eq!(2, g.edges_iter().count());
+        assert_eq!(Err(GraphError::DuplicateEdge(b, b)), g.try_add_edge(b, b));
+        {
+            let marked_vertices = Vec::new();
+            let h = GraphvizBuilderUndirectedImpl::new(&g, &marked_vertices);
+            assert_eq!(1, h.build_string().matches("\"0_1\" -- \"0_1\"").count());
+        }
+        g.remove_edge(e);
+        assert_eq!(1, g.degree(b));
+        assert_eq!(1, g.edges_iter().count());
+        assert_eq!(None, g.edge_handle(e));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut g = UndirectedSimpleGraphImpl::new(BasicGraph::new());
+        for _ in 0..4 {
+            g.create_vertex();
+        }
+        let e01 = g.add_edge(0, 1);
+        let e12 = g.add_edge(1, 2);
+        g.add_edge(2, 3);
+        g.remove_edge(e01);
+        // reuse the freed ids of the twins
+        let e31 = g.add_edge(3, 1);
+        g.remove_vertex(0);
+
+        let compaction = g.compact();
+        assert_eq!(3, g.vertices_size());
+        assert_eq!(6, g.edges_max());
+        assert_eq!(3, g.edges_iter().count());
+        let f = compaction.edge(e31).unwrap();
+        assert_eq!(Some((0, 2)), g.get_vertices_from_edge(f));
+        let f2 = g.get_reversed_edge(f).unwrap();
+        assert_eq!(Some((2, 0)), g.get_vertices_from_edge(f2));
+        assert_eq!(Some(f), g.get_reversed_edge(f2));
+        let f12 = compaction.edge(e12).unwrap();
+        assert_eq!(Some((1, 0)), g.get_vertices_from_edge(f12));
+        assert_eq!(2, g.degree(0));
     }
 }
@@ -0,0 +1,147 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+/// BitSet represents a subset of 0..len, one bit per element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+const WORD_BITS: usize = 64;
+
+impl BitSet {
+    pub fn new(len: usize) -> BitSet {
+        BitSet {
+            words: vec![0; (len + WORD_BITS - 1) / WORD_BITS],
+            len,
+        }
+    }
+
+    /// Add an element. Return false if the element was already in the set.
+    pub fn insert(&mut self, i: usize) -> bool {
+        assert!(i < self.len);
+        let (w, mask) = (i / WORD_BITS, 1 << (i % WORD_BITS));
+        let absent = self.words[w] & mask == 0;
+        self.words[w] |= mask;
+        absent
+    }
+
+    /// Remove an element. Return false if the element was not in the set.
+    pub fn remove(&mut self, i: usize) -> bool {
+        if !self.contains(i) {
+            return false;
+        }
+        self.words[i / WORD_BITS] &= !(1 << (i % WORD_BITS));
+        true
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < self.len && self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+    }
+
+    /// Add all the elements of other to this set (other must have the same len)
+    pub fn union_with(&mut self, other: &BitSet) {
+        assert_eq!(self.len, other.len);
+        for (w, &o) in self.words.iter_mut().zip(other.words.iter()) {
+            *w |= o;
+        }
+    }
+
+    /// The number of elements of the set
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The bound of the elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Return an iterator on the elements, in increasing order.
+    pub fn iter(&self) -> BitSetIter<'_> {
+        BitSetIter {
+            words: &self.words,
+            index: 0,
+            word: self.words.first().cloned().unwrap_or(0),
+        }
+    }
+}
+
+pub struct BitSetIter<'a> {
+    words: &'a [u64],
+    index: usize,
+    word: u64,
+}
+
+impl<'a> Iterator for BitSetIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.index += 1;
+            if self.index >= self.words.len() {
+                return None;
+            }
+            self.word = self.words[self.index];
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.index * WORD_BITS + bit)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bit_set() {
+        let mut set = BitSet::new(130);
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(64));
+        assert!(set.contains(129));
+        assert!(!set.contains(1));
+        assert!(!set.contains(500));
+        assert_eq!(3, set.count());
+        assert_eq!(vec![0, 64, 129], set.iter().collect::<Vec<usize>>());
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        assert_eq!(vec![0, 129], set.iter().collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_bit_set_union() {
+        let mut set1 = BitSet::new(70);
+        let mut set2 = BitSet::new(70);
+        set1.insert(3);
+        set2.insert(3);
+        set2.insert(69);
+        set1.union_with(&set2);
+        assert_eq!(vec![3, 69], set1.iter().collect::<Vec<usize>>());
+        assert_eq!(Vec::<usize>::new(), BitSet::new(0).iter().collect::<Vec<usize>>());
+    }
+}
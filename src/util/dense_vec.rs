@@ -41,7 +41,11 @@ impl<'a, T> DenseVec<'a, T> where T: 'a + Clone + PartialEq {
     /// Return the index of the free place.
     pub fn add_value(&mut self, value: T) -> usize {
         let i = self.indices.index_consume();
-        self.values[i] = value;
+        if i == self.values.len() {
+            self.values.push(value);
+        } else {
+            self.values[i] = value;
+        }
         i
     }
 
@@ -56,11 +60,46 @@ impl<'a, T> DenseVec<'a, T> where T: 'a + Clone + PartialEq {
         }
     }
 
+    /// Set the value at a given place, whether the place is free or not.
+    pub fn set_value(&mut self, e: usize, value: T) {
+        self.indices.use_index(e);
+        if e < self.values.len() {
+            self.values[e] = value;
+        } else {
+            // the holes are filled with copies of the value, but they are free
+            while self.values.len() < e {
+                self.values.push(value.clone());
+            }
+            self.values.push(value);
+        }
+    }
+
     /// Free the place
     pub fn remove_element(&mut self, e: usize) {
         self.indices.free_index(e);
     }
 
+    /// Free the places e such that `keep(e)` is false
+    pub fn retain<F>(&mut self, keep: F) where F: Fn(usize) -> bool {
+        let removed: Vec<usize> = self.indices.used_indices_iter().filter(|&e| !keep(e)).collect();
+        for e in removed {
+            self.indices.free_index(e);
+        }
+    }
+
+    /// Move every value from its place e to the place `new_place(e)`. The value is dropped if
+    /// there is no new place.
+    pub fn remap<F>(&mut self, new_place: F) where F: Fn(usize) -> Option<usize> {
+        let moved: Vec<(usize, T)> = self.indices.used_indices_iter()
+            .filter_map(|e| new_place(e).map(|f| (f, self.values[e].clone())))
+            .collect();
+        self.indices = DenseVecIndices::new();
+        self.values = Vec::with_capacity(moved.len());
+        for (f, value) in moved {
+            self.set_value(f, value);
+        }
+    }
+
     /// Return an iterator on values
     /// heap cost : use into_iter
     pub fn values_iter<'b>(&'b self) -> Box<dyn Iterator<Item=T> + 'b> {
@@ -68,6 +107,12 @@ impl<'a, T> DenseVec<'a, T> where T: 'a + Clone + PartialEq {
         Box::new(it.map(move |e| self.values[e].clone()))
     }
 
+    /// Return an iterator on (place, value)
+    pub fn indexed_values_iter<'b>(&'b self) -> Box<dyn Iterator<Item=(usize, &'b T)> + 'b> {
+        let it = self.indices.used_indices_iter();
+        Box::new(it.map(move |e| (e, &self.values[e])))
+    }
+
     /// Gert the value at the index e
     pub fn get_value(&self, e: usize) -> Option<&T> {
         match self.indices.index_is_free(e) {
@@ -78,8 +123,8 @@ impl<'a, T> DenseVec<'a, T> where T: 'a + Clone + PartialEq {
 
     pub fn get_mut_value(&mut self, e: usize) -> Option<&mut T> {
         match self.indices.index_is_free(e) {
-            false => None,
-            true => Some(&mut self.values[e]),
+            true => None,
+            false => Some(&mut self.values[e]),
         }
     }
 
@@ -88,7 +133,7 @@ impl<'a, T> DenseVec<'a, T> where T: 'a + Clone + PartialEq {
     }
 
     pub fn has_value(&self, e: usize, v: &T) -> bool {
-        self.indices.index_is_free(e) && self.values[e] == *v
+        self.indices.index_is_used(e) && self.values[e] == *v
     }
 
     pub fn size(&self) -> usize {
@@ -148,4 +193,48 @@ mod test {
         let v: Vec<usize> = set.values_iter().collect();
         assert! (v == vec![10, 20, 50, 40]);
     }
+
+    #[test]
+    fn test_dense_vec_set_value() {
+        let mut set: DenseVec<usize> = DenseVec::new();
+        set.add_value_at_place(0, 10);
+        set.set_value(0, 20);
+        set.set_value(3, 40);
+        assert_eq!(set.size(), 2);
+        assert!(!set.has_element(2));
+        assert_eq!(set.get_value(3), Some(&40));
+        let v: Vec<usize> = set.values_iter().collect();
+        assert!(v == vec![20, 40]);
+    }
+
+    #[test]
+    fn test_dense_vec_get_mut_value() {
+        let mut set: DenseVec<usize> = DenseVec::new();
+        assert_eq!(set.add_value(10), 0);
+        assert_eq!(set.add_value(20), 1);
+        *set.get_mut_value(1).unwrap() += 5;
+        assert_eq!(set.get_value(1), Some(&25));
+        assert!(set.has_value(1, &25));
+        assert_eq!(set.get_mut_value(2), None);
+        set.retain(|e| e != 0);
+        assert_eq!(set.get_mut_value(0), None);
+        assert!(!set.has_value(0, &10));
+    }
+
+    #[test]
+    fn test_dense_vec_remap() {
+        let mut set: DenseVec<usize> = DenseVec::new();
+        set.set_value(1, 10);
+        set.set_value(3, 30);
+        set.set_value(4, 40);
+        set.remap(|e| match e {
+            1 => Some(0),
+            4 => Some(1),
+            _ => None,
+        });
+        assert_eq!(set.size(), 2);
+        assert_eq!(set.get_value(0), Some(&10));
+        assert_eq!(set.get_value(1), Some(&40));
+        assert!(!set.has_element(3));
+    }
 }
@@ -68,6 +68,25 @@ impl DenseVecIndices {
         }
     }
 
+    /// Mark an index as used, even if it is not the next index returned by `index_consume`.
+    /// The skipped indices are freed. Return false if the index was already used.
+    pub fn use_index(&mut self, e: usize) -> bool {
+        if self.index_is_used(e) {
+            return false;
+        }
+        if e < self.is_free.len() {
+            self.is_free[e] = false;
+            self.free_elements.retain(|&f| f != e);
+        } else {
+            for f in self.is_free.len()..e {
+                self.is_free.push(true);
+                self.free_elements.push_back(f);
+            }
+            self.is_free.push(false);
+        }
+        true
+    }
+
     /// Free an index.
     pub fn free_index(&mut self, e: usize) -> bool {
         if self.index_is_free(e) {
@@ -181,6 +200,21 @@ mod test {
         assert!(v == vec![0, 1, 2, 3]);
     }
 
+    #[test]
+    fn test_dense_vec_indices_use_index() {
+        let mut set = DenseVecIndices::new();
+        assert!(set.use_index(3));
+        assert!(!set.use_index(3));
+        assert_eq!(set.size(), 1);
+        assert_eq!(set.max(), 4);
+        assert!(set.use_index(1));
+        let v: Vec<usize> = set.used_indices_iter().collect();
+        assert!(v == vec![1, 3]);
+        assert_eq!(set.index_consume(), 0);
+        assert_eq!(set.index_consume(), 2);
+        assert_eq!(set.index_consume(), 4);
+    }
+
     #[test]
     fn test_dense_vec_indices5into_iter() {
         let mut set = DenseVecIndices::new();
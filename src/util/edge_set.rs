@@ -17,7 +17,6 @@
 /// You should have received a copy of the GNU General Public License
 /// along with this program.  If not, see <http://www.gnu.org/licenses/>.
 /// ***************************************************************************
-use std::collections::HashMap;
 use std::collections::hash_map;
 use std::cmp::Eq;
 use std::hash::Hash;
@@ -29,6 +28,8 @@ pub trait EdgeSet<V, E>
           E: Eq + Hash
 {
     type S; // Hashset<E> or E
+    type EdgesByTo; // HashMap<V, S> or Vec<(V, S)>
+    type EdgesByToIter<'a>: Iterator<Item = (&'a V, &'a Self::S)> where Self: 'a, V: 'a, Self::S: 'a;
 
     /// create a new EdgeSet
     fn new() -> Self;
@@ -40,11 +41,19 @@ pub trait EdgeSet<V, E>
     fn remove_edge(&mut self, &V, &V, &E) -> bool;
 
     /// return an iterator on maps vertex -> set
-    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<V, HashMap<V, Self::S>>;
+    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<'_, V, Self::EdgesByTo>;
 
     /// given a `from` vertex, returns an iterator on (`to`, set of edges | optional edge)
-    fn edges_by_to_iter(&self, &V) -> hash_map::Iter<V, Self::S>;
+    fn edges_by_to_iter(&self, &V) -> Self::EdgesByToIter<'_>;
+
+    /// given a `from` vertex, returns the edges from this vertex
+    fn edges_from(&self, from: &V) -> Vec<E> where E: Clone;
 
     /// given a `from` and a `to` vertex, returns a set of edges | optional edge
     fn get_edges(&self, &V, &V) -> Option<&Self::S>;
+
+    /// a new EdgeSet with the edges (u, v, e) mapped by `f`. An edge is dropped if `f` returns None.
+    fn remap<F>(&self, f: F) -> Self
+        where F: FnMut(&V, &V, &E) -> Option<(V, V, E)>,
+              Self: Sized;
 }
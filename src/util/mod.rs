@@ -33,14 +33,19 @@ pub mod graphviz_builder_undirected;
 pub mod edge_set;
 pub mod simple_edge_set;
 pub mod multiple_edge_set;
+pub mod vec_edge_set;
 
 // a compact associative table index -> value
 pub mod dense_vec_indices;
 mod dense_vec;
+
+// a fixed size set of naturals, one bit per element
+pub mod bit_set;
 pub mod iterator_util;
 
 pub use self::edge_set::EdgeSet;
 pub use self::dense_vec::DenseVec;
+pub use self::bit_set::BitSet;
 pub use self::graphviz_writer::GraphvizWriter;
 pub use self::graphviz_painter::GraphvizPainter;
 pub use self::graphviz_builder::GraphvizBuilder;
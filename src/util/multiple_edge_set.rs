@@ -37,6 +37,8 @@ impl<V, E> EdgeSet<V, E> for MultipleEdgeSet<V, E>
           E: Eq + Hash
 {
     type S = HashSet<E>;
+    type EdgesByTo = HashMap<V, HashSet<E>>;
+    type EdgesByToIter<'b> = hash_map::Iter<'b, V, HashSet<E>> where Self: 'b, V: 'b;
 
     fn new() -> Self {
         MultipleEdgeSet {
@@ -84,17 +86,21 @@ impl<V, E> EdgeSet<V, E> for MultipleEdgeSet<V, E>
         ret
     }
 
-    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<V, HashMap<V, HashSet<E>>> {
+    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<'_, V, HashMap<V, HashSet<E>>> {
         self.edges_by_to_by_from.iter()
     }
 
-    fn edges_by_to_iter(&self, u: &V) -> hash_map::Iter<V, HashSet<E>> {
+    fn edges_by_to_iter(&self, u: &V) -> hash_map::Iter<'_, V, HashSet<E>> {
         match self.edges_by_to_by_from.get(u) {
             Some(m) => m.iter(),
             None => self.helper.empty(),
         }
     }
 
+    fn edges_from(&self, u: &V) -> Vec<E> where E: Clone {
+        self.edges_by_to_iter(u).flat_map(|(_, edges)| edges.iter().cloned()).collect()
+    }
+
     fn get_edges(&self, u: &V, v: &V) -> Option<&HashSet<E>> {
         match self.edges_by_to_by_from.get(u) {
             Some(edges_by_to) => {
@@ -103,6 +109,22 @@ impl<V, E> EdgeSet<V, E> for MultipleEdgeSet<V, E>
             None => Option::None,
         }
     }
+
+    fn remap<F>(&self, mut f: F) -> Self
+        where F: FnMut(&V, &V, &E) -> Option<(V, V, E)>
+    {
+        let mut edge_set = Self::new();
+        for (u, edges_by_to) in &self.edges_by_to_by_from {
+            for (v, edges) in edges_by_to {
+                for e in edges {
+                    if let Some((u, v, e)) = f(u, v, e) {
+                        edge_set.add_edge(u, v, e);
+                    }
+                }
+            }
+        }
+        edge_set
+    }
 }
 
 #[cfg(test)]
@@ -145,4 +167,17 @@ mod test {
         set.remove_edge(&1, &2, &0);
         assert!(set.edges_by_to_by_from_iter().next().is_none());
     }
+
+    #[test]
+    fn test_edges_from() {
+        let mut set = MultipleEdgeSet::new();
+        set.add_edge(1, 2, 0);
+        set.add_edge(1, 2, 1);
+        set.add_edge(1, 3, 2);
+        set.add_edge(2, 1, 3);
+        let mut edges = set.edges_from(&1);
+        edges.sort();
+        assert_eq!(vec![0, 1, 2], edges);
+        assert!(set.edges_from(&3).is_empty());
+    }
 }
@@ -35,6 +35,8 @@ impl<'a, V, E> EdgeSet<V, E> for SimpleEdgeSet<V, E>
           E: 'a + Eq + Hash
 {
     type S = E;
+    type EdgesByTo = HashMap<V, E>;
+    type EdgesByToIter<'b> = hash_map::Iter<'b, V, E> where Self: 'b, V: 'b;
 
     fn new() -> Self {
         SimpleEdgeSet {
@@ -80,17 +82,21 @@ impl<'a, V, E> EdgeSet<V, E> for SimpleEdgeSet<V, E>
         ret
     }
 
-    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<V, HashMap<V, E>> {
+    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<'_, V, HashMap<V, E>> {
         self.edges_by_to_by_from.iter()
     }
 
-    fn edges_by_to_iter(&self, u: &V) -> hash_map::Iter<V, E> {
+    fn edges_by_to_iter(&self, u: &V) -> hash_map::Iter<'_, V, E> {
         match self.edges_by_to_by_from.get(u) {
             Some(m) => m.iter(),
             None => self.helper.empty(),
         }
     }
 
+    fn edges_from(&self, u: &V) -> Vec<E> where E: Clone {
+        self.edges_by_to_iter(u).map(|(_, e)| e.clone()).collect()
+    }
+
     fn get_edges(&self, u: &V, v: &V) -> Option<&E> {
         match self.edges_by_to_by_from.get(u) {
             Some(edges_by_to) => {
@@ -99,6 +105,20 @@ impl<'a, V, E> EdgeSet<V, E> for SimpleEdgeSet<V, E>
             None => Option::None,
         }
     }
+
+    fn remap<F>(&self, mut f: F) -> Self
+        where F: FnMut(&V, &V, &E) -> Option<(V, V, E)>
+    {
+        let mut edge_set = Self::new();
+        for (u, edges_by_to) in &self.edges_by_to_by_from {
+            for (v, e) in edges_by_to {
+                if let Some((u, v, e)) = f(u, v, e) {
+                    edge_set.add_edge(u, v, e);
+                }
+            }
+        }
+        edge_set
+    }
 }
 
 #[cfg(test)]
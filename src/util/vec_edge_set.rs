@@ -0,0 +1,158 @@
+/// *****************************************************************************
+/// R-Graphs - A simple graph library for Rust
+/// Copyright (C) 2016-2019 J. Férard <https://github.com/jferard>
+///
+/// This file is part of R-Graphs.
+///
+/// R-Graphs is free software: you can redistribute it and/or modify
+/// it under the terms of the GNU General Public License as published by
+/// the Free Software Foundation, either version 3 of the License, or
+/// (at your option) any later version.
+///
+/// R-Graphs is distributed in the hope that it will be useful,
+/// but WITHOUT ANY WARRANTY; without even the implied warranty of
+/// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+/// GNU General Public License for more details.
+///
+/// You should have received a copy of the GNU General Public License
+/// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+/// ***************************************************************************
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::cmp::Eq;
+use std::hash::Hash;
+use std::iter::Map;
+use std::slice;
+
+use util::edge_set::EdgeSet;
+
+/// An EdgeSet that stores the edges from a vertex in a Vec, in insertion order: iterating on the
+/// neighbors of a vertex is deterministic and cache-friendly. There is at most one edge between
+/// two vertices. Finding or removing an edge is linear in the degree of the vertex.
+pub struct VecEdgeSet<V, E> {
+    edges_by_to_by_from: HashMap<V, Vec<(V, E)>>,
+}
+
+type EdgesByToIter<'a, V, E> = Map<slice::Iter<'a, (V, E)>, fn(&'a (V, E)) -> (&'a V, &'a E)>;
+
+fn as_pair<V, E>(edge: &(V, E)) -> (&V, &E) {
+    (&edge.0, &edge.1)
+}
+
+impl<V, E> EdgeSet<V, E> for VecEdgeSet<V, E>
+    where V: Eq + Hash,
+          E: Eq + Hash
+{
+    type S = E;
+    type EdgesByTo = Vec<(V, E)>;
+    type EdgesByToIter<'b> = EdgesByToIter<'b, V, E> where Self: 'b, V: 'b;
+
+    fn new() -> Self {
+        VecEdgeSet {
+            edges_by_to_by_from: HashMap::new(),
+        }
+    }
+
+    fn add_edge(&mut self, u: V, v: V, e: E) -> bool {
+        let edges_by_to = self.edges_by_to_by_from.entry(u).or_default();
+        if edges_by_to.iter().any(|(w, _)| *w == v) {
+            return false;
+        }
+        edges_by_to.push((v, e));
+        true
+    }
+
+    fn remove_edge(&mut self, u: &V, v: &V, e: &E) -> bool {
+        let len = match self.edges_by_to_by_from.get_mut(u) {
+            Some(edges_by_to) => {
+                match edges_by_to.iter().position(|(w, f)| w == v && f == e) {
+                    Some(i) => {
+                        edges_by_to.remove(i);
+                    }
+                    None => return false,
+                }
+                edges_by_to.len()
+            }
+            None => return false,
+        };
+        if len == 0 {
+            self.edges_by_to_by_from.remove(u);
+        }
+        true
+    }
+
+    fn edges_by_to_by_from_iter(&self) -> hash_map::Iter<'_, V, Vec<(V, E)>> {
+        self.edges_by_to_by_from.iter()
+    }
+
+    fn edges_by_to_iter(&self, u: &V) -> EdgesByToIter<'_, V, E> {
+        let edges_by_to: &[(V, E)] = match self.edges_by_to_by_from.get(u) {
+            Some(edges_by_to) => edges_by_to,
+            None => &[],
+        };
+        edges_by_to.iter().map(as_pair)
+    }
+
+    fn edges_from(&self, u: &V) -> Vec<E> where E: Clone {
+        self.edges_by_to_iter(u).map(|(_, e)| e.clone()).collect()
+    }
+
+    fn get_edges(&self, u: &V, v: &V) -> Option<&E> {
+        self.edges_by_to_by_from.get(u)
+            .and_then(|edges_by_to| edges_by_to.iter().find(|(w, _)| w == v))
+            .map(|(_, e)| e)
+    }
+
+    /// The edges from a vertex keep their order.
+    fn remap<F>(&self, mut f: F) -> Self
+        where F: FnMut(&V, &V, &E) -> Option<(V, V, E)>
+    {
+        let mut edge_set = Self::new();
+        for (u, edges_by_to) in &self.edges_by_to_by_from {
+            for (v, e) in edges_by_to {
+                if let Some((u, v, e)) = f(u, v, e) {
+                    edge_set.add_edge(u, v, e);
+                }
+            }
+        }
+        edge_set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_add_twice() {
+        let mut set = VecEdgeSet::new();
+        assert!(set.add_edge(1, 2, 0));
+        assert!(!set.add_edge(1, 2, 1));
+        assert_eq!(Some(&0), set.get_edges(&1, &2));
+        assert_eq!(None, set.get_edges(&2, &1));
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let mut set = VecEdgeSet::new();
+        for &(v, e) in &[(5, 0), (3, 1), (9, 2), (1, 3)] {
+            set.add_edge(0, v, e);
+        }
+        assert!(set.remove_edge(&0, &9, &2));
+        assert!(!set.remove_edge(&0, &9, &2));
+        assert!(!set.remove_edge(&0, &3, &0));
+        let edges: Vec<(usize, usize)> = set.edges_by_to_iter(&0).map(|(&v, &e)| (v, e)).collect();
+        assert_eq!(vec![(5, 0), (3, 1), (1, 3)], edges);
+        assert!(set.edges_by_to_iter(&7).next().is_none());
+    }
+
+    #[test]
+    fn test_add_remove() {
+        let mut set = VecEdgeSet::new();
+        assert!(set.edges_by_to_by_from_iter().next().is_none());
+        set.add_edge(1, 2, 0);
+        assert!(set.edges_by_to_by_from_iter().next().is_some());
+        set.remove_edge(&1, &2, &0);
+        assert!(set.edges_by_to_by_from_iter().next().is_none());
+    }
+}